@@ -28133,8 +28133,16 @@ unsafe extern "C" {
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct expert_field {
-    _unused: [u8; 0],
+    pub ei: ::std::os::raw::c_int,
+    pub hf: ::std::os::raw::c_int,
 }
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of expert_field"][::std::mem::size_of::<expert_field>() - 8usize];
+    ["Alignment of expert_field"][::std::mem::align_of::<expert_field>() - 4usize];
+    ["Offset of field: expert_field::ei"][::std::mem::offset_of!(expert_field, ei) - 0usize];
+    ["Offset of field: expert_field::hf"][::std::mem::offset_of!(expert_field, hf) - 4usize];
+};
 pub type custom_fmt_func_t =
     ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_char, arg2: u32)>;
 pub type custom_fmt_func_64_t =
@@ -34595,3 +34603,729 @@ pub struct __crt_multibyte_data {
     pub _address: u8,
 }
 pub type __builtin_va_list = *mut ::std::os::raw::c_char;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct expert_field_info {
+    pub name: *const ::std::os::raw::c_char,
+    pub group: ::std::os::raw::c_int,
+    pub severity: ::std::os::raw::c_int,
+    pub summary: *const ::std::os::raw::c_char,
+    pub id: ::std::os::raw::c_int,
+    pub protocol: *const ::std::os::raw::c_char,
+    pub orig_severity: ::std::os::raw::c_int,
+    pub hf_info: hf_register_info,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of expert_field_info"][::std::mem::size_of::<expert_field_info>() - 128usize];
+    ["Alignment of expert_field_info"][::std::mem::align_of::<expert_field_info>() - 8usize];
+    ["Offset of field: expert_field_info::name"]
+        [::std::mem::offset_of!(expert_field_info, name) - 0usize];
+    ["Offset of field: expert_field_info::group"]
+        [::std::mem::offset_of!(expert_field_info, group) - 8usize];
+    ["Offset of field: expert_field_info::severity"]
+        [::std::mem::offset_of!(expert_field_info, severity) - 12usize];
+    ["Offset of field: expert_field_info::summary"]
+        [::std::mem::offset_of!(expert_field_info, summary) - 16usize];
+    ["Offset of field: expert_field_info::id"]
+        [::std::mem::offset_of!(expert_field_info, id) - 24usize];
+    ["Offset of field: expert_field_info::protocol"]
+        [::std::mem::offset_of!(expert_field_info, protocol) - 32usize];
+    ["Offset of field: expert_field_info::orig_severity"]
+        [::std::mem::offset_of!(expert_field_info, orig_severity) - 40usize];
+    ["Offset of field: expert_field_info::hf_info"]
+        [::std::mem::offset_of!(expert_field_info, hf_info) - 48usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ei_register_info {
+    pub ids: *mut expert_field,
+    pub eiinfo: expert_field_info,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of ei_register_info"][::std::mem::size_of::<ei_register_info>() - 136usize];
+    ["Alignment of ei_register_info"][::std::mem::align_of::<ei_register_info>() - 8usize];
+    ["Offset of field: ei_register_info::ids"]
+        [::std::mem::offset_of!(ei_register_info, ids) - 0usize];
+    ["Offset of field: ei_register_info::eiinfo"]
+        [::std::mem::offset_of!(ei_register_info, eiinfo) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct expert_module {
+    _unused: [u8; 0],
+}
+pub type expert_module_t = expert_module;
+unsafe extern "C" {
+    pub fn expert_register_protocol(id: ::std::os::raw::c_int) -> *mut expert_module_t;
+}
+unsafe extern "C" {
+    pub fn expert_register_field_array(
+        module: *mut expert_module_t,
+        ei: *mut ei_register_info,
+        num_records: ::std::os::raw::c_int,
+    );
+}
+unsafe extern "C" {
+    pub fn expert_add_info(
+        pinfo: *mut packet_info,
+        pi: *mut proto_item,
+        eiindex: *mut expert_field,
+    ) -> *mut proto_item;
+}
+unsafe extern "C" {
+    pub fn expert_add_info_format(
+        pinfo: *mut packet_info,
+        pi: *mut proto_item,
+        eiindex: *mut expert_field,
+        format: *const ::std::os::raw::c_char,
+        ...
+    ) -> *mut proto_item;
+}
+unsafe extern "C" {
+    pub fn proto_tree_add_expert(
+        tree: *mut proto_tree,
+        pinfo: *mut packet_info,
+        eiindex: *mut expert_field,
+        tvb: *mut tvbuff_t,
+        start: ::std::os::raw::c_int,
+        length: ::std::os::raw::c_int,
+    ) -> *mut proto_item;
+}
+unsafe extern "C" {
+    pub fn proto_tree_add_expert_format(
+        tree: *mut proto_tree,
+        pinfo: *mut packet_info,
+        eiindex: *mut expert_field,
+        tvb: *mut tvbuff_t,
+        start: ::std::os::raw::c_int,
+        length: ::std::os::raw::c_int,
+        format: *const ::std::os::raw::c_char,
+        ...
+    ) -> *mut proto_item;
+}
+pub const TL_REQUIRES_NOTHING: u32 = 0;
+pub const TL_REQUIRES_PROTO_TREE: u32 = 1;
+pub const TL_REQUIRES_COLUMNS: u32 = 2;
+pub const TL_REQUIRES_ERROR_PACKETS: u32 = 4;
+pub const tap_packet_status_TAP_PACKET_DONT_REDRAW: tap_packet_status = 0;
+pub const tap_packet_status_TAP_PACKET_REDRAW: tap_packet_status = 1;
+pub const tap_packet_status_TAP_PACKET_FAILED: tap_packet_status = 2;
+pub type tap_packet_status = ::std::os::raw::c_int;
+pub type tap_flags_t = ::std::os::raw::c_uint;
+pub type tap_reset_cb =
+    ::std::option::Option<unsafe extern "C" fn(tapdata: *mut ::std::os::raw::c_void)>;
+pub type tap_packet_cb = ::std::option::Option<
+    unsafe extern "C" fn(
+        tapdata: *mut ::std::os::raw::c_void,
+        pinfo: *mut packet_info,
+        edt: *mut epan_dissect_t,
+        data: *const ::std::os::raw::c_void,
+        flags: tap_flags_t,
+    ) -> tap_packet_status,
+>;
+pub type tap_draw_cb =
+    ::std::option::Option<unsafe extern "C" fn(tapdata: *mut ::std::os::raw::c_void)>;
+pub type tap_finish_cb =
+    ::std::option::Option<unsafe extern "C" fn(tapdata: *mut ::std::os::raw::c_void)>;
+unsafe extern "C" {
+    pub fn register_tap_listener(
+        tapname: *const ::std::os::raw::c_char,
+        tapdata: *mut ::std::os::raw::c_void,
+        fstring: *const ::std::os::raw::c_char,
+        flags: ::std::os::raw::c_uint,
+        tap_reset: tap_reset_cb,
+        tap_packet: tap_packet_cb,
+        tap_draw: tap_draw_cb,
+        tap_finish: tap_finish_cb,
+    ) -> *mut GString;
+}
+unsafe extern "C" {
+    pub fn remove_tap_listener(tapdata: *mut ::std::os::raw::c_void);
+}
+unsafe extern "C" {
+    pub fn register_tap(name: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn tap_queue_packet(
+        tap_id: ::std::os::raw::c_int,
+        pinfo: *mut packet_info,
+        tap_specific_data: *const ::std::os::raw::c_void,
+    );
+}
+unsafe extern "C" {
+    pub fn have_tap_listener(tap_id: ::std::os::raw::c_int) -> bool;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct tap_plugin {
+    pub register_tap_listener: ::std::option::Option<unsafe extern "C" fn()>,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of tap_plugin"][::std::mem::size_of::<tap_plugin>() - 8usize];
+    ["Alignment of tap_plugin"][::std::mem::align_of::<tap_plugin>() - 8usize];
+    ["Offset of field: tap_plugin::register_tap_listener"]
+        [::std::mem::offset_of!(tap_plugin, register_tap_listener) - 0usize];
+};
+unsafe extern "C" {
+    pub fn tap_register_plugin(plug: *const tap_plugin);
+}
+pub const ST_FLG_AVERAGE: u32 = 268435456;
+pub const ST_FLG_ROOTCHILD: u32 = 536870912;
+pub const ST_FLG_DEF_NOEXPAND: u32 = 16777216;
+pub const ST_FLG_SORT_DESC: u32 = 8388608;
+pub const ST_FLG_SORT_TOP: u32 = 4194304;
+pub const ST_FLG_SRTCOL_MASK: u32 = 983040;
+pub const ST_FLG_SRTCOL_SHIFT: u32 = 16;
+pub const ST_SORT_COL_NAME: u32 = 1;
+pub const ST_SORT_COL_COUNT: u32 = 2;
+pub const ST_SORT_COL_AVG: u32 = 3;
+pub const ST_SORT_COL_MIN: u32 = 4;
+pub const ST_SORT_COL_MAX: u32 = 5;
+pub const ST_SORT_COL_BURSTRATE: u32 = 6;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _stats_tree {
+    _unused: [u8; 0],
+}
+pub type stats_tree = _stats_tree;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _stats_tree_cfg {
+    _unused: [u8; 0],
+}
+pub type stats_tree_cfg = _stats_tree_cfg;
+pub type stat_tree_packet_cb = ::std::option::Option<
+    unsafe extern "C" fn(
+        arg1: *mut stats_tree,
+        arg2: *mut packet_info,
+        arg3: *mut epan_dissect_t,
+        arg4: *const ::std::os::raw::c_void,
+        flags: tap_flags_t,
+    ) -> tap_packet_status,
+>;
+pub type stat_tree_init_cb = ::std::option::Option<unsafe extern "C" fn(arg1: *mut stats_tree)>;
+pub type stat_tree_cleanup_cb =
+    ::std::option::Option<unsafe extern "C" fn(arg1: *mut stats_tree)>;
+pub const _stat_node_datatype_STAT_DT_INT: _stat_node_datatype = 0;
+pub const _stat_node_datatype_STAT_DT_FLOAT: _stat_node_datatype = 1;
+pub type _stat_node_datatype = ::std::os::raw::c_int;
+pub use self::_stat_node_datatype as stat_node_datatype;
+pub const _manip_node_mode_MN_INCREASE: _manip_node_mode = 0;
+pub const _manip_node_mode_MN_SET: _manip_node_mode = 1;
+pub const _manip_node_mode_MN_AVERAGE: _manip_node_mode = 2;
+pub const _manip_node_mode_MN_AVERAGE_NOTICK: _manip_node_mode = 3;
+pub const _manip_node_mode_MN_SET_FLAGS: _manip_node_mode = 4;
+pub const _manip_node_mode_MN_CLEAR_FLAGS: _manip_node_mode = 5;
+pub type _manip_node_mode = ::std::os::raw::c_int;
+pub use self::_manip_node_mode as manip_node_mode;
+unsafe extern "C" {
+    pub fn stats_tree_register(
+        tapname: *const ::std::os::raw::c_char,
+        abbr: *const ::std::os::raw::c_char,
+        path: *const ::std::os::raw::c_char,
+        flags: ::std::os::raw::c_uint,
+        packet: stat_tree_packet_cb,
+        init: stat_tree_init_cb,
+        cleanup: stat_tree_cleanup_cb,
+    ) -> *mut stats_tree_cfg;
+}
+unsafe extern "C" {
+    pub fn stats_tree_register_plugin(
+        tapname: *const ::std::os::raw::c_char,
+        abbr: *const ::std::os::raw::c_char,
+        path: *const ::std::os::raw::c_char,
+        flags: ::std::os::raw::c_uint,
+        packet: stat_tree_packet_cb,
+        init: stat_tree_init_cb,
+        cleanup: stat_tree_cleanup_cb,
+    ) -> *mut stats_tree_cfg;
+}
+unsafe extern "C" {
+    pub fn stats_tree_set_first_column_name(
+        st_config: *mut stats_tree_cfg,
+        column_name: *const ::std::os::raw::c_char,
+    );
+}
+unsafe extern "C" {
+    pub fn stats_tree_create_node(
+        st: *mut stats_tree,
+        name: *const ::std::os::raw::c_char,
+        parent_id: ::std::os::raw::c_int,
+        datatype: stat_node_datatype,
+        with_children: bool,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn stats_tree_create_pivot(
+        st: *mut stats_tree,
+        name: *const ::std::os::raw::c_char,
+        parent_id: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn stats_tree_manip_node_int(
+        mode: manip_node_mode,
+        st: *mut stats_tree,
+        name: *const ::std::os::raw::c_char,
+        parent_id: ::std::os::raw::c_int,
+        with_children: bool,
+        value: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn stats_tree_tick_pivot(
+        st: *mut stats_tree,
+        pivot_id: ::std::os::raw::c_int,
+        pivot_value: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn address_to_str(
+        scope: *mut wmem_allocator_t,
+        addr: *const address,
+    ) -> *mut ::std::os::raw::c_char;
+}
+pub const CONV_FILTER_INVALID: &[u8; 8] = b"INVALID\0";
+pub const conv_filter_type_e_CONV_FT_SRC_ADDRESS: conv_filter_type_e = 0;
+pub const conv_filter_type_e_CONV_FT_DST_ADDRESS: conv_filter_type_e = 1;
+pub const conv_filter_type_e_CONV_FT_ANY_ADDRESS: conv_filter_type_e = 2;
+pub const conv_filter_type_e_CONV_FT_SRC_PORT: conv_filter_type_e = 3;
+pub const conv_filter_type_e_CONV_FT_DST_PORT: conv_filter_type_e = 4;
+pub const conv_filter_type_e_CONV_FT_ANY_PORT: conv_filter_type_e = 5;
+pub type conv_filter_type_e = ::std::os::raw::c_int;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _conversation_hashtable {
+    pub hashtable: *mut GHashTable,
+    pub conv_array: *mut GArray,
+    pub user_data: *mut ::std::os::raw::c_void,
+    pub flags: ::std::os::raw::c_uint,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _conversation_hashtable"][::std::mem::size_of::<_conversation_hashtable>() - 32usize];
+    ["Alignment of _conversation_hashtable"]
+        [::std::mem::align_of::<_conversation_hashtable>() - 8usize];
+    ["Offset of field: _conversation_hashtable::hashtable"]
+        [::std::mem::offset_of!(_conversation_hashtable, hashtable) - 0usize];
+    ["Offset of field: _conversation_hashtable::conv_array"]
+        [::std::mem::offset_of!(_conversation_hashtable, conv_array) - 8usize];
+    ["Offset of field: _conversation_hashtable::user_data"]
+        [::std::mem::offset_of!(_conversation_hashtable, user_data) - 16usize];
+    ["Offset of field: _conversation_hashtable::flags"]
+        [::std::mem::offset_of!(_conversation_hashtable, flags) - 24usize];
+};
+pub type conv_hash_t = _conversation_hashtable;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _conversation_item_t {
+    _unused: [u8; 0],
+}
+pub type conv_item_t = _conversation_item_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _endpoint_item_t {
+    _unused: [u8; 0],
+}
+pub type endpoint_item_t = _endpoint_item_t;
+pub type conv_get_filter_type = ::std::option::Option<
+    unsafe extern "C" fn(
+        item: *mut _conversation_item_t,
+        filter: conv_filter_type_e,
+    ) -> *const ::std::os::raw::c_char,
+>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _ct_dissector_info {
+    pub get_filter_type: conv_get_filter_type,
+}
+pub type ct_dissector_info_t = _ct_dissector_info;
+pub type endpoint_get_filter_type = ::std::option::Option<
+    unsafe extern "C" fn(
+        item: *mut _endpoint_item_t,
+        filter_type: conv_filter_type_e,
+    ) -> *const ::std::os::raw::c_char,
+>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _et_dissector_info {
+    pub get_filter_type: endpoint_get_filter_type,
+}
+pub type et_dissector_info_t = _et_dissector_info;
+unsafe extern "C" {
+    pub fn register_conversation_table(
+        proto_id: ::std::os::raw::c_int,
+        hide_ports: bool,
+        conv_packet_func: tap_packet_cb,
+        endpoint_packet_func: tap_packet_cb,
+    );
+}
+unsafe extern "C" {
+    pub fn add_conversation_table_data(
+        ch: *mut conv_hash_t,
+        src: *const address,
+        dst: *const address,
+        src_port: u32,
+        dst_port: u32,
+        num_frames: ::std::os::raw::c_int,
+        num_bytes: ::std::os::raw::c_int,
+        ts: *mut nstime_t,
+        abs_ts: *mut nstime_t,
+        ct_info: *mut ct_dissector_info_t,
+        ctype: conversation_type,
+    );
+}
+unsafe extern "C" {
+    pub fn add_endpoint_table_data(
+        ch: *mut conv_hash_t,
+        addr: *const address,
+        port: u32,
+        sender: bool,
+        num_frames: ::std::os::raw::c_int,
+        num_bytes: ::std::os::raw::c_int,
+        et_info: *mut et_dissector_info_t,
+        etype: endpoint_type,
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _srt_procedure_t {
+    _unused: [u8; 0],
+}
+pub type srt_procedure_t = _srt_procedure_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _srt_stat_table {
+    pub name: *mut ::std::os::raw::c_char,
+    pub short_name: *mut ::std::os::raw::c_char,
+    pub filter_string: *mut ::std::os::raw::c_char,
+    pub num_procs: ::std::os::raw::c_int,
+    pub proc_column_name: *mut ::std::os::raw::c_char,
+    pub procedures: *mut srt_procedure_t,
+    pub table_specific_data: *mut ::std::os::raw::c_void,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _srt_stat_table"][::std::mem::size_of::<_srt_stat_table>() - 56usize];
+    ["Alignment of _srt_stat_table"][::std::mem::align_of::<_srt_stat_table>() - 8usize];
+    ["Offset of field: _srt_stat_table::name"][::std::mem::offset_of!(_srt_stat_table, name) - 0usize];
+    ["Offset of field: _srt_stat_table::short_name"]
+        [::std::mem::offset_of!(_srt_stat_table, short_name) - 8usize];
+    ["Offset of field: _srt_stat_table::filter_string"]
+        [::std::mem::offset_of!(_srt_stat_table, filter_string) - 16usize];
+    ["Offset of field: _srt_stat_table::num_procs"]
+        [::std::mem::offset_of!(_srt_stat_table, num_procs) - 24usize];
+    ["Offset of field: _srt_stat_table::proc_column_name"]
+        [::std::mem::offset_of!(_srt_stat_table, proc_column_name) - 32usize];
+    ["Offset of field: _srt_stat_table::procedures"]
+        [::std::mem::offset_of!(_srt_stat_table, procedures) - 40usize];
+    ["Offset of field: _srt_stat_table::table_specific_data"]
+        [::std::mem::offset_of!(_srt_stat_table, table_specific_data) - 48usize];
+};
+pub type srt_stat_table = _srt_stat_table;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _register_srt {
+    _unused: [u8; 0],
+}
+pub type register_srt_t = _register_srt;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _srt_data_t {
+    pub srt_array: *mut GArray,
+    pub user_data: *mut ::std::os::raw::c_void,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _srt_data_t"][::std::mem::size_of::<_srt_data_t>() - 16usize];
+    ["Alignment of _srt_data_t"][::std::mem::align_of::<_srt_data_t>() - 8usize];
+    ["Offset of field: _srt_data_t::srt_array"][::std::mem::offset_of!(_srt_data_t, srt_array) - 0usize];
+    ["Offset of field: _srt_data_t::user_data"][::std::mem::offset_of!(_srt_data_t, user_data) - 8usize];
+};
+pub type srt_data_t = _srt_data_t;
+pub type srt_init_cb =
+    ::std::option::Option<unsafe extern "C" fn(srt: *mut _register_srt, srt_array: *mut GArray)>;
+pub type srt_param_handler_cb = ::std::option::Option<
+    unsafe extern "C" fn(
+        srt: *mut register_srt_t,
+        opt_arg: *const ::std::os::raw::c_char,
+        err: *mut *mut ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_uint,
+>;
+unsafe extern "C" {
+    pub fn register_srt_table(
+        proto_id: ::std::os::raw::c_int,
+        tap_listener: *const ::std::os::raw::c_char,
+        max_params: ::std::os::raw::c_int,
+        srt_packet_func: tap_packet_cb,
+        init_cb: srt_init_cb,
+        param_cb: srt_param_handler_cb,
+    );
+}
+unsafe extern "C" {
+    pub fn init_srt_table(
+        name: *const ::std::os::raw::c_char,
+        short_name: *const ::std::os::raw::c_char,
+        srt_array: *mut GArray,
+        num_procs: ::std::os::raw::c_int,
+        proc_column_name: *const ::std::os::raw::c_char,
+        filter_string: *const ::std::os::raw::c_char,
+        table_specific_data: *mut ::std::os::raw::c_void,
+    ) -> *mut srt_stat_table;
+}
+unsafe extern "C" {
+    pub fn init_srt_table_row(
+        rst: *mut srt_stat_table,
+        proc_index: ::std::os::raw::c_int,
+        procedure: *const ::std::os::raw::c_char,
+    );
+}
+unsafe extern "C" {
+    pub fn add_srt_table_data(
+        rst: *mut srt_stat_table,
+        proc_index: ::std::os::raw::c_int,
+        req_time: *const nstime_t,
+        pinfo: *mut packet_info,
+    );
+}
+pub type follow_conv_filter_func = ::std::option::Option<
+    unsafe extern "C" fn(
+        edt: *mut epan_dissect_t,
+        pinfo: *mut packet_info,
+        stream: *mut ::std::os::raw::c_uint,
+        sub_stream: *mut ::std::os::raw::c_uint,
+    ) -> *mut ::std::os::raw::c_char,
+>;
+pub type follow_index_filter_func = ::std::option::Option<
+    unsafe extern "C" fn(
+        stream: ::std::os::raw::c_uint,
+        sub_stream: ::std::os::raw::c_uint,
+    ) -> *mut ::std::os::raw::c_char,
+>;
+pub type follow_address_filter_func = ::std::option::Option<
+    unsafe extern "C" fn(
+        src_addr: *mut address,
+        dst_addr: *mut address,
+        src_port: ::std::os::raw::c_int,
+        dst_port: ::std::os::raw::c_int,
+    ) -> *mut ::std::os::raw::c_char,
+>;
+pub type follow_port_to_display_func = ::std::option::Option<
+    unsafe extern "C" fn(
+        allocator: *mut wmem_allocator_t,
+        port: ::std::os::raw::c_uint,
+    ) -> *mut ::std::os::raw::c_char,
+>;
+pub type follow_stream_count_func = ::std::option::Option<unsafe extern "C" fn() -> u32>;
+pub type follow_sub_stream_id_func = ::std::option::Option<
+    unsafe extern "C" fn(
+        stream: ::std::os::raw::c_uint,
+        sub_stream: ::std::os::raw::c_uint,
+        le: bool,
+        sub_stream_out: *mut ::std::os::raw::c_uint,
+    ) -> bool,
+>;
+unsafe extern "C" {
+    pub fn register_follow_stream(
+        proto_id: ::std::os::raw::c_int,
+        tap_listener: *const ::std::os::raw::c_char,
+        conv_filter: follow_conv_filter_func,
+        index_filter: follow_index_filter_func,
+        address_filter: follow_address_filter_func,
+        port_to_display: follow_port_to_display_func,
+        tap_handler: tap_packet_cb,
+        stream_count: follow_stream_count_func,
+        sub_stream_id: follow_sub_stream_id_func,
+    );
+}
+unsafe extern "C" {
+    pub fn follow_tvb_tap_listener(
+        tapdata: *mut ::std::os::raw::c_void,
+        pinfo: *mut packet_info,
+        edt: *mut epan_dissect_t,
+        data: *const ::std::os::raw::c_void,
+        flags: tap_flags_t,
+    ) -> tap_packet_status;
+}
+pub const MAX_NUM_NODES: u32 = 40;
+pub const seq_analysis_info_type_t_SEQ_ANALYSIS_ANY: seq_analysis_info_type_t = 0;
+pub const seq_analysis_info_type_t_SEQ_ANALYSIS_TCP: seq_analysis_info_type_t = 1;
+pub const seq_analysis_info_type_t_SEQ_ANALYSIS_VOIP: seq_analysis_info_type_t = 2;
+pub type seq_analysis_info_type_t = ::std::os::raw::c_int;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _seq_analysis_item {
+    pub frame_number: u32,
+    pub src_addr: address,
+    pub port_src: u16,
+    pub dst_addr: address,
+    pub port_dst: u16,
+    pub frame_label: *mut ::std::os::raw::c_char,
+    pub time_str: *mut ::std::os::raw::c_char,
+    pub comment: *mut ::std::os::raw::c_char,
+    pub conv_num: u16,
+    pub fg_color: ::std::os::raw::c_uint,
+    pub bg_color: ::std::os::raw::c_uint,
+    pub has_color_filter: bool,
+    pub display: bool,
+    pub src_node: ::std::os::raw::c_uint,
+    pub dst_node: ::std::os::raw::c_uint,
+    pub line_style: u16,
+    pub info_type: seq_analysis_info_type_t,
+    pub info_ptr: *mut ::std::os::raw::c_void,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _seq_analysis_item"][::std::mem::size_of::<_seq_analysis_item>() - 136usize];
+    ["Alignment of _seq_analysis_item"][::std::mem::align_of::<_seq_analysis_item>() - 8usize];
+    ["Offset of field: _seq_analysis_item::src_addr"]
+        [::std::mem::offset_of!(_seq_analysis_item, src_addr) - 8usize];
+    ["Offset of field: _seq_analysis_item::dst_addr"]
+        [::std::mem::offset_of!(_seq_analysis_item, dst_addr) - 40usize];
+    ["Offset of field: _seq_analysis_item::frame_label"]
+        [::std::mem::offset_of!(_seq_analysis_item, frame_label) - 72usize];
+    ["Offset of field: _seq_analysis_item::comment"]
+        [::std::mem::offset_of!(_seq_analysis_item, comment) - 88usize];
+};
+pub type seq_analysis_item_t = _seq_analysis_item;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _seq_analysis_info {
+    pub name: *const ::std::os::raw::c_char,
+    pub any_addr: bool,
+    pub items: *mut GQueue,
+    pub ht: *mut GHashTable,
+    pub nodes: [address; 40usize],
+    pub num_nodes: u8,
+}
+pub type seq_analysis_info_t = _seq_analysis_info;
+unsafe extern "C" {
+    pub fn register_seq_analysis(
+        name: *const ::std::os::raw::c_char,
+        ui_name: *const ::std::os::raw::c_char,
+        proto_id: ::std::os::raw::c_int,
+        tap_listener: *const ::std::os::raw::c_char,
+        tap_flags: ::std::os::raw::c_uint,
+        tap_func: tap_packet_cb,
+    );
+}
+unsafe extern "C" {
+    pub fn sequence_analysis_create_sai_with_addresses(
+        pinfo: *mut packet_info,
+        sainfo: *mut seq_analysis_info_t,
+    ) -> *mut seq_analysis_item_t;
+}
+unsafe extern "C" {
+    pub fn sequence_analysis_use_color_filter(pinfo: *mut packet_info, sai: *mut seq_analysis_item_t);
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _export_object_entry_t {
+    pub pkt_num: u32,
+    pub hostname: *mut ::std::os::raw::c_char,
+    pub content_type: *mut ::std::os::raw::c_char,
+    pub filename: *mut ::std::os::raw::c_char,
+    pub payload_len: usize,
+    pub payload_data: *mut u8,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _export_object_entry_t"][::std::mem::size_of::<_export_object_entry_t>() - 48usize];
+    ["Alignment of _export_object_entry_t"]
+        [::std::mem::align_of::<_export_object_entry_t>() - 8usize];
+};
+pub type export_object_entry_t = _export_object_entry_t;
+pub type export_object_object_list_add_entry_cb = ::std::option::Option<
+    unsafe extern "C" fn(gui_data: *mut ::std::os::raw::c_void, entry: *mut _export_object_entry_t),
+>;
+pub type export_object_object_list_get_entry_cb = ::std::option::Option<
+    unsafe extern "C" fn(
+        gui_data: *mut ::std::os::raw::c_void,
+        row: ::std::os::raw::c_int,
+    ) -> *mut export_object_entry_t,
+>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _export_object_list_t {
+    pub add_entry: export_object_object_list_add_entry_cb,
+    pub get_entry: export_object_object_list_get_entry_cb,
+    pub gui_data: *mut ::std::os::raw::c_void,
+}
+pub type export_object_list_t = _export_object_list_t;
+pub type export_object_gui_reset_cb = ::std::option::Option<unsafe extern "C" fn()>;
+unsafe extern "C" {
+    pub fn register_export_object(
+        proto_id: ::std::os::raw::c_int,
+        export_packet_func: tap_packet_cb,
+        reset_cb: export_object_gui_reset_cb,
+    ) -> ::std::os::raw::c_int;
+}
+pub const register_stat_group_t_REGISTER_PACKET_ANALYZE_GROUP_UNSORTED: register_stat_group_t = 0;
+pub const register_stat_group_t_REGISTER_PACKET_STAT_GROUP_UNSORTED: register_stat_group_t = 1;
+pub const register_stat_group_t_REGISTER_STAT_GROUP_GENERIC: register_stat_group_t = 2;
+pub type register_stat_group_t = ::std::os::raw::c_int;
+pub const param_type_PARAM_UINT: param_type = 0;
+pub const param_type_PARAM_STRING: param_type = 1;
+pub const param_type_PARAM_ENUM: param_type = 2;
+pub const param_type_PARAM_UUID: param_type = 3;
+pub const param_type_PARAM_FILTER: param_type = 4;
+pub type param_type = ::std::os::raw::c_int;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _tap_param {
+    pub type_: param_type,
+    pub name: *const ::std::os::raw::c_char,
+    pub title: *const ::std::os::raw::c_char,
+    pub enum_vals: *const enum_val_t,
+    pub optional: bool,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _tap_param"][::std::mem::size_of::<_tap_param>() - 40usize];
+    ["Alignment of _tap_param"][::std::mem::align_of::<_tap_param>() - 8usize];
+    ["Offset of field: _tap_param::type_"][::std::mem::offset_of!(_tap_param, type_) - 0usize];
+    ["Offset of field: _tap_param::name"][::std::mem::offset_of!(_tap_param, name) - 8usize];
+    ["Offset of field: _tap_param::title"][::std::mem::offset_of!(_tap_param, title) - 16usize];
+    ["Offset of field: _tap_param::enum_vals"]
+        [::std::mem::offset_of!(_tap_param, enum_vals) - 24usize];
+    ["Offset of field: _tap_param::optional"]
+        [::std::mem::offset_of!(_tap_param, optional) - 32usize];
+};
+pub type tap_param = _tap_param;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _stat_tap_ui {
+    pub group: register_stat_group_t,
+    pub title: *const ::std::os::raw::c_char,
+    pub cli_string: *const ::std::os::raw::c_char,
+    pub tap_init_cb: ::std::option::Option<
+        unsafe extern "C" fn(
+            arg1: *const ::std::os::raw::c_char,
+            arg2: *mut ::std::os::raw::c_void,
+        ) -> bool,
+    >,
+    pub nparams: usize,
+    pub params: *mut tap_param,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _stat_tap_ui"][::std::mem::size_of::<_stat_tap_ui>() - 48usize];
+    ["Alignment of _stat_tap_ui"][::std::mem::align_of::<_stat_tap_ui>() - 8usize];
+    ["Offset of field: _stat_tap_ui::group"][::std::mem::offset_of!(_stat_tap_ui, group) - 0usize];
+    ["Offset of field: _stat_tap_ui::title"][::std::mem::offset_of!(_stat_tap_ui, title) - 8usize];
+    ["Offset of field: _stat_tap_ui::cli_string"]
+        [::std::mem::offset_of!(_stat_tap_ui, cli_string) - 16usize];
+    ["Offset of field: _stat_tap_ui::tap_init_cb"]
+        [::std::mem::offset_of!(_stat_tap_ui, tap_init_cb) - 24usize];
+    ["Offset of field: _stat_tap_ui::nparams"]
+        [::std::mem::offset_of!(_stat_tap_ui, nparams) - 32usize];
+    ["Offset of field: _stat_tap_ui::params"]
+        [::std::mem::offset_of!(_stat_tap_ui, params) - 40usize];
+};
+pub type stat_tap_ui = _stat_tap_ui;
+unsafe extern "C" {
+    pub fn register_stat_tap_ui(ui: *mut stat_tap_ui, callback_data: *mut ::std::os::raw::c_void);
+}
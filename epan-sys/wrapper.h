@@ -7,5 +7,14 @@
 #include <epan/conversation.h>
 #include <epan/dissectors/packet-tcp.h>
 #include <epan/proto_data.h>
+#include <epan/expert.h>
+#include <epan/tap.h>
+#include <epan/stats_tree.h>
+#include <epan/conversation_table.h>
+#include <epan/srt_table.h>
+#include <epan/follow.h>
+#include <epan/sequence_analysis.h>
+#include <epan/export_object.h>
+#include <epan/stat_tap_ui.h>
 
 #endif // EPAN_SYS
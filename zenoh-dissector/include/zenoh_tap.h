@@ -0,0 +1,68 @@
+/*
+ * Record queued by the Zenoh dissector to the "zenoh" tap, one per message.
+ *
+ * Transport messages are queued with a NULL `network`. The network messages of a Frame or
+ * reassembled from Fragments, and the messages of the low-latency transport, are queued on their
+ * own. Strings are NULL when not applicable and live at least as long as the dissection of the
+ * packet.
+ *
+ * Mirrors `TapRecord` in zenoh-dissector/src/tap.rs. Fields are only ever appended; check
+ * `version` before reading fields newer than your copy of this header.
+ */
+
+#ifndef ZENOH_TAP_H
+#define ZENOH_TAP_H
+
+#include <stdbool.h>
+#include <stdint.h>
+#include <wsutil/nstime.h>
+
+#define ZENOH_TAP_NAME "zenoh"
+#define ZENOH_TAP_RECORD_VERSION 1
+
+typedef struct zenoh_tap_record {
+    uint32_t version;
+    /* Transport message, e.g. "InitSyn" or "Frame". */
+    const char *transport;
+    /* Network message, e.g. "Push" or "Request". */
+    const char *network;
+    /* Zenoh message: "Put", "Del", "Query", "Reply" or "Err". */
+    const char *zenoh;
+    /* Length of the encoded message in bytes, or 0 for a network message of a compressed batch. */
+    uint32_t len;
+    /* Whether the message is carried by a Frame or Fragments, whose own records already count
+     * its bytes. */
+    bool in_frame;
+    /* ZIDs of the sender and receiver, NULL if the handshake of the session wasn't captured. */
+    const char *src_zid;
+    const char *dst_zid;
+    /* Nodes of the sender and receiver, i.e. their ZID followed by their WhatAmI. */
+    const char *src;
+    const char *dst;
+    /* Resolved key expression of a Push, Request or Response, or "scope:mapping:suffix". */
+    const char *keyexpr;
+    /* Length of the payload of a Push, Request or Response in bytes. */
+    uint32_t payload_len;
+    /* Id of a Request, or of the Request a Response or ResponseFinal answers. */
+    bool has_request_id;
+    uint32_t request_id;
+    /* Capture time of the packet. */
+    nstime_t abs_ts;
+    /* Timestamp of the data, or else of the network message, as "<NTP64 time>/<ZID>". */
+    const char *timestamp;
+    /* QoS of network messages. Priority is 0 to 7 and congestion control 0 (drop), 1 (block) or
+     * 2 (block first); both are -1 for transport messages. */
+    const char *reliability;
+    int priority;
+    int congestion_control;
+    bool express;
+    /* Frame number (0 if not captured), capture time and resolved key expression of the Request
+     * a Response or ResponseFinal answers. */
+    uint32_t request_frame;
+    nstime_t request_ts;
+    const char *request_keyexpr;
+    /* ZID of the queryable that sent a Response, if announced. */
+    const char *responder_zid;
+} zenoh_tap_record;
+
+#endif /* ZENOH_TAP_H */
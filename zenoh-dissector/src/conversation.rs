@@ -1,35 +1,815 @@
 use std::{
-    ffi::{c_char, CStr, CString},
-    mem, ptr,
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    mem, ptr, slice,
 };
 
-use zenoh_protocol::transport::{TransportBody, TransportMessage};
+use zenoh_buffers::{buffer::SplitBuffer, reader::HasReader};
+use zenoh_codec::{RCodec, Zenoh080Reliability};
+use zenoh_protocol::{
+    core::{
+        resolution::{Bits, Field},
+        Priority, Reliability, Resolution, WhatAmI, WireExpr, ZenohIdProto,
+    },
+    network::{DeclareBody, Mapping, NetworkBody, NetworkMessage, RequestId},
+    transport::{
+        init, Fragment, Frame, TransportBody, TransportBodyLowLatency, TransportMessage,
+        TransportMessageLowLatency,
+    },
+};
 
-use crate::{ws_log, PROTOCOL_DATA};
+use crate::{
+    expert,
+    header_field::{FieldKind, HeaderFieldMap, Registration},
+    linkstate,
+    tree::TreeArgs,
+    wireshark::{set_generated, set_hidden},
+    ws_log, MessageBody, PROTOCOL_DATA,
+};
 
 pub const FIELD_SRCZID: &str = "zenoh.srczid";
 pub const FIELD_DSTZID: &str = "zenoh.dstzid";
+pub const FIELD_SRC: &str = "zenoh.src";
+pub const FIELD_DST: &str = "zenoh.dst";
+pub const FIELD_NODE: &str = "zenoh.node";
+pub const SESSION_PREFIX: &str = "zenoh.session";
+pub const TIMING_PREFIX: &str = "zenoh.timing";
+
+/// Key of the per-frame [`FrameTiming`], next to `PROTO_DATA_KEY_FRAME`.
+const PROTO_DATA_KEY_TIMING: u32 = 1;
+/// Key of the per-frame list of [`Reassembled`] messages.
+const PROTO_DATA_KEY_REASSEMBLED: u32 = 2;
+/// Key of the per-frame [`Resync`] of a stream captured mid-session.
+const PROTO_DATA_KEY_RESYNC: u32 = 3;
+
+/// Number of sessions started in the capture, which index them in order of appearance.
+static mut SESSION_COUNT: u32 = 0;
 
+/// Number of KeepAlives a peer sends per lease period when idle, i.e. the default of the
+/// `transport/link/tx/keep_alive` configuration.
+const KEEP_ALIVE_FACTOR: u64 = 4;
+
+/// Parameters of a session as negotiated by its InitSyn/InitAck/OpenSyn/OpenAck handshake.
+///
+/// Fields prefixed with `syn_` hold what the initiator offered; the others hold the effective
+/// value once the acceptor answered.
 #[derive(Debug)]
 #[repr(C)]
-struct ConversationState {
-    /// C string representing the InitSyn sender's (or "A") ZID of the conversation.
+struct SessionParams {
+    /// Frame numbers of the handshake messages, or 0 if not yet seen.
+    init_syn_frame: u32,
+    init_ack_frame: u32,
+    open_syn_frame: u32,
+    /// Batches of frames after the OpenAck are sent with the negotiated link configuration.
+    open_ack_frame: u32,
+    /// Initial sequence numbers announced in the OpenSyn and OpenAck.
+    initiator_initial_sn: u32,
+    acceptor_initial_sn: u32,
+    /// Sequence number and request id resolution. The acceptor answers with the agreed value.
+    resolution: u8,
+    syn_batch_size: u16,
+    /// Minimum of the batch sizes announced by both sides.
+    batch_size: u16,
+    /// Lease announced by the initiator in the OpenSyn, in milliseconds.
+    initiator_lease: u64,
+    /// Lease announced by the acceptor in the OpenAck, in milliseconds.
+    acceptor_lease: u64,
+    syn_qos: bool,
+    /// Whether QoS was negotiated through either `ext_qos` or `ext_qos_link`.
+    qos: bool,
+    syn_qos_link: bool,
+    /// Whether both sides carry `ext_qos_link`; `qos_link` is then the acceptor's value.
+    has_qos_link: bool,
+    qos_link: u64,
+    syn_shm: bool,
+    shm: bool,
+    syn_lowlatency: bool,
+    /// Whether the low-latency transport was negotiated, i.e. both InitSyn and InitAck carry
+    /// `ext_lowlatency`.
+    lowlatency: bool,
+    syn_compression: bool,
+    /// Whether compression was negotiated, i.e. both InitSyn and InitAck carry `ext_compression`.
+    compression: bool,
+    syn_patch: u8,
+    /// Minimum of the patch versions supported by both sides.
+    patch: u8,
+    /// C strings of the `ext_region_name` of each side, or null if absent.
+    initiator_region_name: *const c_char,
+    acceptor_region_name: *const c_char,
+}
+
+impl SessionParams {
+    fn new() -> Self {
+        SessionParams {
+            init_syn_frame: 0,
+            init_ack_frame: 0,
+            open_syn_frame: 0,
+            open_ack_frame: 0,
+            initiator_initial_sn: 0,
+            acceptor_initial_sn: 0,
+            resolution: 0,
+            syn_batch_size: 0,
+            batch_size: 0,
+            initiator_lease: 0,
+            acceptor_lease: 0,
+            syn_qos: false,
+            qos: false,
+            syn_qos_link: false,
+            has_qos_link: false,
+            qos_link: 0,
+            syn_shm: false,
+            shm: false,
+            syn_lowlatency: false,
+            lowlatency: false,
+            syn_compression: false,
+            compression: false,
+            syn_patch: 0,
+            patch: 0,
+            initiator_region_name: ptr::null(),
+            acceptor_region_name: ptr::null(),
+        }
+    }
+
+    /// Record what the initiator offered in its InitSyn.
+    fn offer(&mut self, init_syn: &init::InitSyn) {
+        self.syn_batch_size = init_syn.batch_size;
+        self.syn_qos = init_syn.ext_qos.is_some();
+        self.syn_qos_link = init_syn.ext_qos_link.is_some();
+        self.syn_shm = init_syn.ext_shm.is_some();
+        self.syn_lowlatency = init_syn.ext_lowlatency.is_some();
+        self.syn_compression = init_syn.ext_compression.is_some();
+        self.syn_patch = init_syn.ext_patch.raw();
+    }
+
+    /// Settle the effective values from the offer and the acceptor's answer in its InitAck.
+    fn negotiate(&mut self, init_ack: &init::InitAck) {
+        self.resolution = init_ack.resolution.as_u8();
+        self.batch_size = self.syn_batch_size.min(init_ack.batch_size);
+        self.qos = (self.syn_qos || self.syn_qos_link)
+            && (init_ack.ext_qos.is_some() || init_ack.ext_qos_link.is_some());
+        if let (true, Some(qos_link)) = (self.syn_qos_link, &init_ack.ext_qos_link) {
+            self.has_qos_link = true;
+            self.qos_link = qos_link.value;
+        }
+        self.shm = self.syn_shm && init_ack.ext_shm.is_some();
+        self.lowlatency = self.syn_lowlatency && init_ack.ext_lowlatency.is_some();
+        self.compression = self.syn_compression && init_ack.ext_compression.is_some();
+        self.patch = self.syn_patch.min(init_ack.ext_patch.raw());
+    }
+
+    /// Add the negotiated values as generated fields of the session subtree.
+    unsafe fn add_to_tree(
+        &self,
+        tvb: *mut epan_sys::tvbuff,
+        tree: *mut epan_sys::_proto_node,
+        hf: impl Fn(&str) -> c_int,
+    ) {
+        let resolution = CString::new(resolution_summary(self.resolution.into())).unwrap();
+        set_generated(epan_sys::proto_tree_add_string(
+            tree,
+            hf("resolution"),
+            tvb,
+            0,
+            0,
+            resolution.as_ptr(),
+        ));
+
+        for (key, value) in [
+            ("batch_size", self.batch_size as u64),
+            ("initiator_lease", self.initiator_lease),
+            ("acceptor_lease", self.acceptor_lease),
+            ("patch", self.patch as u64),
+        ] {
+            set_generated(epan_sys::proto_tree_add_uint64(
+                tree,
+                hf(key),
+                tvb,
+                0,
+                0,
+                value,
+            ));
+        }
+
+        for (key, value) in [
+            ("qos", self.qos),
+            ("shm", self.shm),
+            ("lowlatency", self.lowlatency),
+            ("compression", self.compression),
+        ] {
+            set_generated(epan_sys::proto_tree_add_boolean(
+                tree,
+                hf(key),
+                tvb,
+                0,
+                0,
+                value as u64,
+            ));
+        }
+
+        if self.has_qos_link {
+            let qos_link = CString::new(qos_link_summary(self.qos_link)).unwrap();
+            set_generated(epan_sys::proto_tree_add_string(
+                tree,
+                hf("qos_link"),
+                tvb,
+                0,
+                0,
+                qos_link.as_ptr(),
+            ));
+        }
+
+        for (key, name) in [
+            ("initiator_region_name", self.initiator_region_name),
+            ("acceptor_region_name", self.acceptor_region_name),
+        ] {
+            if !name.is_null() {
+                set_generated(epan_sys::proto_tree_add_string(
+                    tree,
+                    hf(key),
+                    tvb,
+                    0,
+                    0,
+                    name,
+                ));
+            }
+        }
+    }
+
+    /// Add the parameters assumed for a session joined late as generated fields of the session
+    /// subtree, i.e. those the dissection depends on.
+    unsafe fn add_assumed_to_tree(
+        &self,
+        tvb: *mut epan_sys::tvbuff,
+        tree: *mut epan_sys::_proto_node,
+        hf: impl Fn(&str) -> c_int,
+    ) {
+        let resolution = CString::new(resolution_summary(self.resolution.into())).unwrap();
+        set_generated(epan_sys::proto_tree_add_string(
+            tree,
+            hf("resolution"),
+            tvb,
+            0,
+            0,
+            resolution.as_ptr(),
+        ));
+        for (key, value) in [
+            ("lowlatency", self.lowlatency),
+            ("compression", self.compression),
+        ] {
+            set_generated(epan_sys::proto_tree_add_boolean(
+                tree,
+                hf(key),
+                tvb,
+                0,
+                0,
+                value as u64,
+            ));
+        }
+    }
+}
+
+impl Registration for Session {
+    fn generate_hf_map(prefix: &str) -> HeaderFieldMap {
+        [
+            ("index", "Session Index", FieldKind::Number),
+            ("init_syn_frame", "InitSyn Frame", FieldKind::FrameNum),
+            ("init_ack_frame", "InitAck Frame", FieldKind::FrameNum),
+            ("open_syn_frame", "OpenSyn Frame", FieldKind::FrameNum),
+            ("open_ack_frame", "OpenAck Frame", FieldKind::FrameNum),
+            ("close_frame", "Close Frame", FieldKind::FrameNum),
+            ("teardown_frame", "TCP Teardown Frame", FieldKind::FrameNum),
+            ("state", "State", FieldKind::Text),
+            (
+                "handshake_duration",
+                "Handshake Duration",
+                FieldKind::Duration,
+            ),
+            ("age", "Session Age", FieldKind::Duration),
+            ("resolution", "Resolution", FieldKind::Text),
+            ("batch_size", "Batch Size", FieldKind::Number),
+            ("initiator_lease", "Initiator Lease (ms)", FieldKind::Number),
+            ("acceptor_lease", "Acceptor Lease (ms)", FieldKind::Number),
+            ("qos", "QoS", FieldKind::Boolean),
+            ("qos_link", "QoS Link", FieldKind::Text),
+            ("shm", "Shared Memory", FieldKind::Boolean),
+            ("lowlatency", "Low Latency", FieldKind::Boolean),
+            ("compression", "Compression", FieldKind::Boolean),
+            ("patch", "Patch", FieldKind::Number),
+            ("joined_late", "Joined Late", FieldKind::Boolean),
+            (
+                "initiator_region_name",
+                "Initiator Region Name",
+                FieldKind::Text,
+            ),
+            (
+                "acceptor_region_name",
+                "Acceptor Region Name",
+                FieldKind::Text,
+            ),
+        ]
+        .into_iter()
+        .fold(
+            HeaderFieldMap::new().add(prefix.to_string(), "Session parameters", FieldKind::Branch),
+            |hf_map, (key, name, kind)| hf_map.add(format!("{prefix}.{key}"), name, kind),
+        )
+    }
+
+    fn generate_subtree_names(prefix: &str) -> Vec<String> {
+        vec![prefix.to_string()]
+    }
+}
+
+fn resolution_summary(resolution: Resolution) -> String {
+    format!(
+        "FrameSN: {}, RequestID: {}",
+        resolution.get(Field::FrameSN),
+        resolution.get(Field::RequestID)
+    )
+}
+
+/// Decode the `ext_qos_link` value, see `zenoh_transport::unicast::establishment::ext::qos`.
+fn qos_link_summary(value: u64) -> String {
+    let mut parts = vec![];
+    if value & 0b010 != 0 {
+        let priority = |shift: u64| {
+            Priority::try_from(((value >> shift) & 0xff) as u8)
+                .map(|p| p.to_string())
+                .unwrap_or_else(|_| "invalid".to_string())
+        };
+        parts.push(format!("Priorities: {}..={}", priority(3), priority(3 + 8)));
+    }
+    if value & 0b100 != 0 {
+        let reliability = Reliability::from((value >> (3 + 8 + 8)) & 0x1 == 1);
+        parts.push(format!("Reliability: {reliability:?}"));
+    }
+    if parts.is_empty() {
+        format!("{value:#x}")
+    } else {
+        parts.join(", ")
+    }
+}
+
+fn region_name(ext: &Option<init::ext::RegionName>) -> *const c_char {
+    match ext {
+        Some(ext) => file_scoped_c_str(String::from_utf8_lossy(&ext.value.contiguous()).as_bytes()),
+        None => ptr::null(),
+    }
+}
+
+/// Returns a file-scoped C string naming a node by its ZID and WhatAmI, e.g. `1a2b (router)`.
+fn node_name(zid: &ZenohIdProto, whatami: WhatAmI) -> *mut c_char {
+    file_scoped_c_str(format!("{zid} ({whatami})"))
+}
+
+fn file_scoped_c_str(s: impl AsRef<[u8]>) -> *mut c_char {
+    let s = CString::new(s.as_ref()).unwrap();
+    unsafe { epan_sys::wmem_strdup(epan_sys::wmem_file_scope(), s.as_ptr()) }
+}
+
+/// Network address and port of one side of a session.
+#[derive(Debug)]
+#[repr(C)]
+struct Endpoint {
+    /// Wireshark `address_type` of the address.
+    addr_type: c_int,
+    addr_len: c_int,
+    /// File-scoped copy of the address bytes, or null if `addr_len` is 0.
+    addr: *const u8,
+    port: u32,
+}
+
+impl Endpoint {
+    const fn new() -> Self {
+        Endpoint {
+            addr_type: 0,
+            addr_len: 0,
+            addr: ptr::null(),
+            port: 0,
+        }
+    }
+
+    /// Returns the source endpoint of this packet.
+    unsafe fn source(pinfo: *mut epan_sys::_packet_info) -> Self {
+        let src = &(*pinfo).src;
+        let addr = if src.len > 0 && !src.data.is_null() {
+            epan_sys::wmem_memdup(epan_sys::wmem_file_scope(), src.data, src.len as usize)
+                as *const u8
+        } else {
+            ptr::null()
+        };
+
+        Endpoint {
+            addr_type: src.type_,
+            addr_len: src.len,
+            addr,
+            port: (*pinfo).srcport,
+        }
+    }
+
+    /// Returns whether the endpoint isn't known yet.
+    fn is_unset(&self) -> bool {
+        self.addr_len == 0 && self.port == 0
+    }
+
+    /// Returns whether this packet was sent from this endpoint.
+    unsafe fn is_source(&self, pinfo: *mut epan_sys::_packet_info) -> bool {
+        let src = &(*pinfo).src;
+        if self.port != (*pinfo).srcport || self.addr_type != src.type_ || self.addr_len != src.len
+        {
+            return false;
+        }
+
+        self.addr_len == 0
+            || slice::from_raw_parts(self.addr, self.addr_len as usize)
+                == slice::from_raw_parts(src.data as *const u8, src.len as usize)
+    }
+}
+
+/// A session established by one handshake on a conversation. A conversation holds as many
+/// sessions as handshakes were captured on it, e.g. after a reconnection on the same 5-tuple.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Session {
+    /// Index of the session in the capture, see [`session_count`].
+    index: u32,
+    /// C string representing the InitSyn sender's (or "A") ZID of the session.
     a_zid: *const c_char,
-    /// Source port number of A->B messages.
-    a_port: u16,
-    /// C string representing the InitSyn receiver's (or "B") ZID of the conversation.
+    /// Source endpoint of A->B messages.
+    a: Endpoint,
+    /// C string naming A as a node, i.e. its ZID followed by its WhatAmI.
+    a_node: *const c_char,
+    /// C string representing the InitSyn receiver's (or "B") ZID of the session.
     b_zid: *const c_char,
-    /// Source port number of B->A messages.
-    b_port: u16,
+    /// Source endpoint of B->A messages.
+    b: Endpoint,
+    /// C string naming B as a node, i.e. its ZID followed by its WhatAmI.
+    b_node: *const c_char,
+    /// Parameters negotiated by the handshake.
+    params: SessionParams,
+    /// Capture time of the frame that started the session.
+    start_ts: epan_sys::nstime_t,
+    /// Capture time of the OpenAck frame.
+    open_ack_ts: epan_sys::nstime_t,
+    /// Frame number of the first Close, or 0 if not yet seen.
+    close_frame: u32,
+    /// Whether the first Close was sent by A, so that the Close replied by the other peer can
+    /// be told apart from messages sent after it.
+    closed_by_a: bool,
+    /// Frame number of the first TCP FIN or RST following the Close, or 0 if not yet seen.
+    teardown_frame: u32,
+    /// Frame number and sequence number of the first Frame/Fragment sent by the acceptor after
+    /// the OpenAck, or 0 if not yet seen.
+    acceptor_first_sn_frame: u32,
+    acceptor_first_sn: u32,
+    /// Whether the session started before the capture, i.e. its first message isn't part of
+    /// the handshake. Its parameters are then assumed from the preferences and its ZIDs
+    /// inferred from the messages carrying them.
+    joined_late: bool,
+}
+
+/// Lifecycle of a session as driven by its handshake and Close messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionState {
+    InitSent,
+    InitAcked,
+    OpenSent,
+    Open,
+    Closed,
+    JoinedLate,
+}
+
+impl SessionState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::InitSent => "InitSyn sent",
+            Self::InitAcked => "InitAck received",
+            Self::OpenSent => "OpenSyn sent",
+            Self::Open => "Open",
+            Self::Closed => "Closed",
+            Self::JoinedLate => "Joined late",
+        }
+    }
+}
+
+impl Session {
+    unsafe fn new(pinfo: *mut epan_sys::_packet_info) -> Self {
+        Session {
+            index: 0,
+            a_zid: ptr::null(),
+            a: Endpoint::new(),
+            a_node: ptr::null(),
+            b_zid: ptr::null(),
+            b: Endpoint::new(),
+            b_node: ptr::null(),
+            params: SessionParams::new(),
+            start_ts: (*pinfo).abs_ts,
+            open_ack_ts: (*pinfo).abs_ts,
+            close_frame: 0,
+            closed_by_a: false,
+            teardown_frame: 0,
+            acceptor_first_sn_frame: 0,
+            acceptor_first_sn: 0,
+            joined_late: false,
+        }
+    }
+
+    /// Returns the state of the session once all messages of frame `num` are processed.
+    fn state_at(&self, num: u32) -> SessionState {
+        let reached = |frame: u32| frame != 0 && frame <= num;
+        let params = &self.params;
+        if reached(self.close_frame) {
+            SessionState::Closed
+        } else if reached(params.open_ack_frame) {
+            SessionState::Open
+        } else if reached(params.open_syn_frame) {
+            SessionState::OpenSent
+        } else if reached(params.init_ack_frame) {
+            SessionState::InitAcked
+        } else if self.joined_late {
+            SessionState::JoinedLate
+        } else {
+            SessionState::InitSent
+        }
+    }
+
+    /// Returns the resolution agreed by the InitAck, assumed for sessions joined late, or the
+    /// default one if neither applies.
+    fn resolution(&self) -> Resolution {
+        if self.params.init_ack_frame != 0 || self.joined_late {
+            Resolution::from(self.params.resolution)
+        } else {
+            Resolution::default()
+        }
+    }
+
+    /// Record the first Close of the session and start following the TCP teardown after it.
+    unsafe fn close(&mut self, pinfo: *mut epan_sys::_packet_info) {
+        if self.close_frame == 0 {
+            self.close_frame = (*pinfo).num;
+            self.closed_by_a = self.a.is_source(pinfo);
+            track_teardown(pinfo);
+        }
+    }
+
+    /// Returns whether this packet was sent by the acceptor (or "B") of the session.
+    unsafe fn is_from_acceptor(&self, pinfo: *mut epan_sys::_packet_info) -> bool {
+        !self.b_zid.is_null() && self.b.is_source(pinfo)
+    }
+
+    /// Add the "Session parameters" subtree of generated fields: handshake frames, state and
+    /// timing, and the negotiated parameters once the handshake is over, or the assumed ones if
+    /// the session joined late.
+    unsafe fn add_to_tree(
+        &self,
+        tvb: *mut epan_sys::tvbuff,
+        pinfo: *mut epan_sys::_packet_info,
+        tree: *mut epan_sys::_proto_node,
+    ) {
+        let num = (*pinfo).num;
+        PROTOCOL_DATA.with_borrow(|data| {
+            let hf = |key: &str| data.hf_map[&format!("{SESSION_PREFIX}.{key}")];
+
+            let ti = epan_sys::proto_tree_add_item(
+                tree,
+                data.hf_map[SESSION_PREFIX],
+                tvb,
+                0,
+                0,
+                epan_sys::ENC_NA,
+            );
+            set_generated(ti);
+            let tree = epan_sys::proto_item_add_subtree(ti, data.st_map[SESSION_PREFIX]);
+
+            set_generated(epan_sys::proto_tree_add_uint64(
+                tree,
+                hf("index"),
+                tvb,
+                0,
+                0,
+                self.index as u64,
+            ));
+
+            let params = &self.params;
+            for (key, frame) in [
+                ("init_syn_frame", params.init_syn_frame),
+                ("init_ack_frame", params.init_ack_frame),
+                ("open_syn_frame", params.open_syn_frame),
+                ("open_ack_frame", params.open_ack_frame),
+                ("close_frame", self.close_frame),
+                ("teardown_frame", self.teardown_frame),
+            ] {
+                if frame != 0 {
+                    set_generated(epan_sys::proto_tree_add_uint(
+                        tree,
+                        hf(key),
+                        tvb,
+                        0,
+                        0,
+                        frame,
+                    ));
+                }
+            }
+
+            let state = CString::new(self.state_at(num).as_str()).unwrap();
+            set_generated(epan_sys::proto_tree_add_string(
+                tree,
+                hf("state"),
+                tvb,
+                0,
+                0,
+                state.as_ptr(),
+            ));
+
+            let mut delta = epan_sys::nstime_t { secs: 0, nsecs: 0 };
+            if params.open_ack_frame != 0 && params.open_ack_frame <= num {
+                epan_sys::nstime_delta(&mut delta, &self.open_ack_ts, &self.start_ts);
+                set_generated(epan_sys::proto_tree_add_time(
+                    tree,
+                    hf("handshake_duration"),
+                    tvb,
+                    0,
+                    0,
+                    &delta,
+                ));
+            }
+            epan_sys::nstime_delta(&mut delta, &(*pinfo).abs_ts, &self.start_ts);
+            set_generated(epan_sys::proto_tree_add_time(
+                tree,
+                hf("age"),
+                tvb,
+                0,
+                0,
+                &delta,
+            ));
+
+            if params.open_ack_frame != 0 && num > params.open_ack_frame {
+                params.add_to_tree(tvb, tree, hf);
+            } else if self.joined_late {
+                set_generated(epan_sys::proto_tree_add_boolean(
+                    tree,
+                    hf("joined_late"),
+                    tvb,
+                    0,
+                    0,
+                    1,
+                ));
+                params.add_assumed_to_tree(tvb, tree, hf);
+            }
+        });
+    }
+
+    /// Returns the source ZID for this packet, or `None` if not yet known.
+    unsafe fn source(&self, pinfo: *mut epan_sys::_packet_info) -> Option<*const c_char> {
+        if !self.a_zid.is_null() && self.a.is_source(pinfo) {
+            Some(self.a_zid)
+        } else if !self.b_zid.is_null() && self.b.is_source(pinfo) {
+            Some(self.b_zid)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the source and destination nodes of this packet, each `None` if not yet known.
+    unsafe fn nodes(
+        &self,
+        pinfo: *mut epan_sys::_packet_info,
+    ) -> (Option<*const c_char>, Option<*const c_char>) {
+        let known = |node: *const c_char| (!node.is_null()).then_some(node);
+        if !self.a_zid.is_null() && self.a.is_source(pinfo) {
+            (known(self.a_node), known(self.b_node))
+        } else if !self.b_zid.is_null() && self.b.is_source(pinfo) {
+            (known(self.b_node), known(self.a_node))
+        } else {
+            (None, None)
+        }
+    }
+
+    /// Returns the destination ZID for this packet, or `None` if not yet known.
+    unsafe fn destination(&self, pinfo: *mut epan_sys::_packet_info) -> Option<*const c_char> {
+        if !self.a_zid.is_null() && self.a.is_source(pinfo) {
+            (!self.b_zid.is_null()).then_some(self.b_zid)
+        } else if !self.b_zid.is_null() && self.b.is_source(pinfo) {
+            (!self.a_zid.is_null()).then_some(self.a_zid)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+struct ConversationState {
+    /// Sessions of the conversation, keyed by the number of the frame that started them.
+    sessions: *mut epan_sys::wmem_tree_t,
+    /// Both directions of the conversation, in order of appearance.
+    peers: [Peer; 2],
+}
+
+/// Timing and declarations of the messages sent by one side of a conversation.
+#[derive(Debug)]
+#[repr(C)]
+struct Peer {
+    source: Endpoint,
+    /// Frame number of the last message sent by this peer, or 0 if none yet.
+    last_frame: u32,
+    last_ts: epan_sys::nstime_t,
+    /// Lease announced by the last Join of this peer, in milliseconds, or 0 if none.
+    join_lease: u64,
+    /// Key expressions declared by this peer, keyed by expression id. Each entry is a tree of
+    /// resolved key expressions keyed by the frame number of their declaration; an empty string
+    /// marks an undeclaration.
+    keyexprs: *mut epan_sys::wmem_tree_t,
+    /// Requests sent by this peer, keyed by request id, each a tree of [`RequestInfo`] keyed by
+    /// frame number as ids may be reused.
+    requests: *mut epan_sys::wmem_tree_t,
+    /// Fragments of the message being reassembled, as [`PendingFragments`] keyed by channel.
+    fragments: *mut epan_sys::wmem_tree_t,
+    /// Whether a batch of this peer's stream was decoded, so that the following ones can be
+    /// trusted to start on a batch boundary.
+    synced: bool,
+    /// ZIDs of the nodes of this peer's link states, keyed by PSID, each a tree of C strings
+    /// keyed by the frame number of the link state which carried it.
+    psids: *mut epan_sys::wmem_tree_t,
+}
+
+impl Peer {
+    unsafe fn new() -> Self {
+        Peer {
+            source: Endpoint::new(),
+            last_frame: 0,
+            last_ts: epan_sys::nstime_t { secs: 0, nsecs: 0 },
+            join_lease: 0,
+            keyexprs: epan_sys::wmem_tree_new(epan_sys::wmem_file_scope()),
+            requests: epan_sys::wmem_tree_new(epan_sys::wmem_file_scope()),
+            fragments: epan_sys::wmem_tree_new(epan_sys::wmem_file_scope()),
+            synced: false,
+            psids: epan_sys::wmem_tree_new(epan_sys::wmem_file_scope()),
+        }
+    }
+}
+
+/// Fragments received so far of a network message, during the first pass.
+#[derive(Debug)]
+#[repr(C)]
+struct PendingFragments {
+    first_frame: u32,
+    /// Sequence number of the last fragment.
+    last_sn: u32,
+    count: u32,
+    /// Payloads of the fragments, concatenated.
+    data: *mut epan_sys::wmem_array_t,
+}
+
+/// A network message reassembled from Fragments, stored for the frame of its last Fragment.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Reassembled {
+    /// Channel and sequence number of the last Fragment, which identify it within its frame.
+    channel: u32,
+    sn: u32,
+    /// Frame number of the first Fragment.
+    pub first_frame: u32,
+    /// Number of Fragments of the message.
+    pub count: u32,
+    pub len: u32,
+    /// File-scoped bytes of the message.
+    pub data: *const u8,
+}
+
+impl Reassembled {
+    pub(crate) unsafe fn bytes(&self) -> &[u8] {
+        slice::from_raw_parts(self.data, self.len as usize)
+    }
+
+    /// Decodes the network message, which inherits the reliability of its Fragments.
+    pub(crate) unsafe fn decode(&self, reliability: Reliability) -> Option<NetworkMessage> {
+        let mut reader = self.bytes().reader();
+        Zenoh080Reliability::new(reliability).read(&mut reader).ok()
+    }
+}
+
+/// Channel of a Fragment: messages are fragmented independently per reliability and priority.
+fn fragment_channel(fragment: &Fragment) -> u32 {
+    let reliable = matches!(fragment.reliability, Reliability::Reliable) as u32;
+    (reliable << 3) | fragment.ext_qos.priority() as u32
+}
+
+/// Silence of the sender of a frame, stored per frame during the first pass.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct FrameTiming {
+    /// Frame number of the previous message of the same sender.
+    prev_frame: u32,
+    /// Time elapsed since that message.
+    since_prev: epan_sys::nstime_t,
+    /// Lease announced by the sender, in milliseconds, or 0 if unknown.
+    lease: u64,
 }
 
 impl ConversationState {
-    pub(crate) fn new() -> Self {
+    pub(crate) unsafe fn new() -> Self {
         ConversationState {
-            a_zid: ptr::null_mut(),
-            a_port: u16::default(),
-            b_zid: ptr::null_mut(),
-            b_port: u16::default(),
+            sessions: epan_sys::wmem_tree_new(epan_sys::wmem_file_scope()),
+            peers: [Peer::new(), Peer::new()],
         }
     }
 
@@ -59,102 +839,1215 @@ impl ConversationState {
         }
     }
 
-    /// Returns the source ZID for this packet, or `None` if not yet known.
-    unsafe fn source(&self, pinfo: *mut epan_sys::_packet_info) -> Option<*const c_char> {
-        if !self.a_zid.is_null() && (*pinfo).srcport == self.a_port as u32 {
-            Some(self.a_zid)
-        } else if !self.b_zid.is_null() && (*pinfo).srcport == self.b_port as u32 {
-            Some(self.b_zid)
-        } else {
-            None
+    /// Returns the state of this packet's conversation, or null if Zenoh never attached any.
+    unsafe fn find(pinfo: *mut epan_sys::_packet_info) -> *mut ConversationState {
+        let conv = epan_sys::find_conversation_pinfo(pinfo, 0);
+        if conv.is_null() {
+            return ptr::null_mut();
         }
+
+        let proto = PROTOCOL_DATA.with_borrow(|data| data.id);
+        epan_sys::conversation_get_proto_data(conv, proto) as *mut ConversationState
     }
 
-    /// Returns the destination ZID for this packet, or `None` if not yet known.
-    unsafe fn destination(&self, pinfo: *mut epan_sys::_packet_info) -> Option<*const c_char> {
-        if !self.a_zid.is_null() && (*pinfo).srcport == self.a_port as u32 {
-            if self.b_zid.is_null() {
-                None
+    /// Returns the session this packet belongs to, i.e. the latest one started at or before
+    /// this frame, or null if none.
+    unsafe fn session(&self, pinfo: *mut epan_sys::_packet_info) -> *mut Session {
+        epan_sys::wmem_tree_lookup32_le(self.sessions, (*pinfo).num) as *mut Session
+    }
+
+    /// Record the message(s) of this frame against the silence of their sender, once per frame.
+    ///
+    /// The lease of the sender is the one it announced in the handshake of the current session
+    /// (OpenSyn for the initiator, OpenAck for the acceptor) or in its last Join.
+    unsafe fn track_sender(&mut self, pinfo: *mut epan_sys::_packet_info, join_lease: Option<u64>) {
+        let proto = PROTOCOL_DATA.with_borrow(|data| data.id);
+        let scope = epan_sys::wmem_file_scope();
+        if !epan_sys::p_get_proto_data(scope, pinfo, proto, PROTO_DATA_KEY_TIMING).is_null() {
+            return;
+        }
+
+        let session = self.session(pinfo);
+        let Some(peer) = self.peer_mut(pinfo) else {
+            return;
+        };
+
+        if let Some(lease) = join_lease {
+            peer.join_lease = lease;
+        }
+
+        if peer.last_frame != 0 {
+            let lease = if session.is_null() || (*session).joined_late {
+                peer.join_lease
+            } else if (*session).a.is_source(pinfo) {
+                (*session).params.initiator_lease
+            } else if (*session).b.is_source(pinfo) {
+                (*session).params.acceptor_lease
             } else {
-                Some(self.b_zid)
+                peer.join_lease
+            };
+
+            let timing =
+                epan_sys::wmem_alloc0(scope, mem::size_of::<FrameTiming>()) as *mut FrameTiming;
+            let mut since_prev = epan_sys::nstime_t { secs: 0, nsecs: 0 };
+            epan_sys::nstime_delta(&mut since_prev, &(*pinfo).abs_ts, &peer.last_ts);
+            timing.write(FrameTiming {
+                prev_frame: peer.last_frame,
+                since_prev,
+                lease,
+            });
+            epan_sys::p_add_proto_data(scope, pinfo, proto, PROTO_DATA_KEY_TIMING, timing as _);
+        }
+
+        peer.last_frame = (*pinfo).num;
+        peer.last_ts = (*pinfo).abs_ts;
+    }
+
+    /// Returns the peer sending this packet, assigning it the first unused one if it's new.
+    unsafe fn peer_mut(&mut self, pinfo: *mut epan_sys::_packet_info) -> Option<&mut Peer> {
+        let index = self
+            .peers
+            .iter()
+            .position(|peer| peer.source.is_source(pinfo))
+            .or_else(|| self.peers.iter().position(|peer| peer.source.is_unset()))?;
+        let peer = &mut self.peers[index];
+        if peer.source.is_unset() {
+            peer.source = Endpoint::source(pinfo);
+        }
+        Some(peer)
+    }
+
+    /// Returns the index in `peers` of the sender of this packet, if known.
+    unsafe fn sender(&self, pinfo: *mut epan_sys::_packet_info) -> Option<usize> {
+        self.peers
+            .iter()
+            .position(|peer| peer.last_frame != 0 && peer.source.is_source(pinfo))
+    }
+
+    /// Resolves a wire expression sent in this packet against the key expressions declared
+    /// up to this frame, by the sender or by the receiver depending on its mapping.
+    unsafe fn resolve(
+        &self,
+        pinfo: *mut epan_sys::_packet_info,
+        expr: &WireExpr,
+    ) -> Option<String> {
+        if expr.scope == 0 {
+            return Some(expr.suffix.to_string());
+        }
+
+        let sender = self.sender(pinfo)?;
+        let declarer = match expr.mapping {
+            Mapping::Sender => sender,
+            Mapping::Receiver => 1 - sender,
+        };
+        let prefix = lookup_at_frame(
+            self.peers[declarer].keyexprs,
+            expr.scope as u32,
+            (*pinfo).num,
+        ) as *const c_char;
+        if prefix.is_null() || *prefix == 0 {
+            return None;
+        }
+        Some(CStr::from_ptr(prefix).to_string_lossy().into_owned() + &expr.suffix)
+    }
+
+    /// Record the key expressions (un)declared by a network message of this packet.
+    unsafe fn declare(&mut self, pinfo: *mut epan_sys::_packet_info, msg: &NetworkMessage) {
+        let NetworkBody::Declare(declare) = &msg.body else {
+            return;
+        };
+        let (id, keyexpr) = match &declare.body {
+            DeclareBody::DeclareKeyExpr(decl) => (
+                decl.id,
+                self.resolve(pinfo, &decl.wire_expr).unwrap_or_default(),
+            ),
+            DeclareBody::UndeclareKeyExpr(undecl) => (undecl.id, String::new()),
+            _ => return,
+        };
+        let Some(sender) = self.sender(pinfo) else {
+            return;
+        };
+
+        insert_at_frame(
+            self.peers[sender].keyexprs,
+            id as u32,
+            (*pinfo).num,
+            file_scoped_c_str(keyexpr) as _,
+        );
+    }
+
+    /// Record a Request of this packet, to be matched with its responses.
+    unsafe fn track_request(&mut self, pinfo: *mut epan_sys::_packet_info, msg: &NetworkMessage) {
+        let NetworkBody::Request(request) = &msg.body else {
+            return;
+        };
+        let Some(sender) = self.sender(pinfo) else {
+            return;
+        };
+
+        let keyexpr = self
+            .resolve(pinfo, &request.wire_expr)
+            .unwrap_or_else(|| request.wire_expr.to_string());
+        let info = epan_sys::wmem_alloc0(epan_sys::wmem_file_scope(), mem::size_of::<RequestInfo>())
+            as *mut RequestInfo;
+        info.write(RequestInfo {
+            frame: (*pinfo).num,
+            ts: (*pinfo).abs_ts,
+            keyexpr: file_scoped_c_str(keyexpr),
+        });
+        insert_at_frame(
+            self.peers[sender].requests,
+            request.id,
+            (*pinfo).num,
+            info as _,
+        );
+    }
+
+    /// Record the ZIDs carried by the link states of a network message of this packet, which
+    /// later ones refer to by PSID only. The sender names itself with PSID 0.
+    unsafe fn track_link_states(
+        &mut self,
+        pinfo: *mut epan_sys::_packet_info,
+        msg: &NetworkMessage,
+    ) {
+        let Some(states) = linkstate::link_states(msg) else {
+            return;
+        };
+        let Some(sender) = self.sender(pinfo) else {
+            return;
+        };
+
+        for state in &states {
+            let Some(zid) = &state.zid else {
+                continue;
+            };
+            insert_at_frame(
+                self.peers[sender].psids,
+                state.psid as u32,
+                (*pinfo).num,
+                file_scoped_c_str(zid.to_string()) as _,
+            );
+            if state.psid == 0 {
+                self.infer_zid(pinfo, zid, state.whatami);
             }
-        } else if !self.b_zid.is_null() && (*pinfo).srcport == self.b_port as u32 {
-            if self.a_zid.is_null() {
-                None
-            } else {
-                Some(self.a_zid)
+        }
+    }
+
+    /// Append a Fragment of this packet to the message pending on its channel. Returns the
+    /// message once its last Fragment is received.
+    ///
+    /// A Fragment whose sequence number doesn't follow the pending ones (i.e. after a loss), or
+    /// marked as first, starts a new message. The Drop extension discards the pending message.
+    unsafe fn defragment(
+        &mut self,
+        pinfo: *mut epan_sys::_packet_info,
+        fragment: &Fragment,
+    ) -> *const Reassembled {
+        let Some(sender) = self.sender(pinfo) else {
+            return ptr::null();
+        };
+        let scope = epan_sys::wmem_file_scope();
+        let fragments = self.peers[sender].fragments;
+        let channel = fragment_channel(fragment);
+
+        let mut pending = epan_sys::wmem_tree_lookup32(fragments, channel) as *mut PendingFragments;
+        if fragment.ext_drop.is_some() {
+            if !pending.is_null() {
+                epan_sys::wmem_tree_remove32(fragments, channel);
             }
+            return ptr::null();
+        }
+
+        let session = self.session(pinfo);
+        let mask = if session.is_null() {
+            Resolution::default()
         } else {
-            None
+            (*session).resolution()
+        }
+        .get(Field::FrameSN)
+        .mask();
+        let follows = !pending.is_null()
+            && fragment.ext_first.is_none()
+            && fragment.sn as u64 == ((*pending).last_sn as u64 + 1) & mask;
+        if !follows {
+            pending = epan_sys::wmem_alloc0(scope, mem::size_of::<PendingFragments>()) as _;
+            pending.write(PendingFragments {
+                first_frame: (*pinfo).num,
+                last_sn: fragment.sn,
+                count: 0,
+                data: epan_sys::wmem_array_new(scope, 1),
+            });
+            epan_sys::wmem_tree_insert32(fragments, channel, pending as _);
+        }
+
+        let payload = fragment.payload.as_slice();
+        epan_sys::wmem_array_append((*pending).data, payload.as_ptr() as _, payload.len() as _);
+        (*pending).last_sn = fragment.sn;
+        (*pending).count += 1;
+        if fragment.more {
+            return ptr::null();
+        }
+
+        epan_sys::wmem_tree_remove32(fragments, channel);
+        let len = epan_sys::wmem_array_get_count((*pending).data);
+        let reassembled =
+            epan_sys::wmem_alloc0(scope, mem::size_of::<Reassembled>()) as *mut Reassembled;
+        reassembled.write(Reassembled {
+            channel,
+            sn: fragment.sn,
+            first_frame: (*pending).first_frame,
+            count: (*pending).count,
+            len,
+            data: epan_sys::wmem_array_finalize((*pending).data) as _,
+        });
+
+        let proto = PROTOCOL_DATA.with_borrow(|data| data.id);
+        let mut list = epan_sys::p_get_proto_data(scope, pinfo, proto, PROTO_DATA_KEY_REASSEMBLED)
+            as *mut epan_sys::wmem_list_t;
+        if list.is_null() {
+            list = epan_sys::wmem_list_new(scope);
+            epan_sys::p_add_proto_data(scope, pinfo, proto, PROTO_DATA_KEY_REASSEMBLED, list as _);
+        }
+        epan_sys::wmem_list_append(list, reassembled as _);
+        reassembled
+    }
+
+    /// Starts a new session at this frame.
+    unsafe fn start_session(&mut self, pinfo: *mut epan_sys::_packet_info) -> *mut Session {
+        let session = epan_sys::wmem_alloc0(epan_sys::wmem_file_scope(), mem::size_of::<Session>())
+            as *mut Session;
+        session.write(Session {
+            index: SESSION_COUNT,
+            ..Session::new(pinfo)
+        });
+        SESSION_COUNT += 1;
+        epan_sys::wmem_tree_insert32(self.sessions, (*pinfo).num, session as *mut _);
+        session
+    }
+
+    /// Starts a session at this frame for a conversation whose handshake wasn't captured, with
+    /// the link configuration and resolution assumed by the preferences. The sender of this
+    /// packet becomes A.
+    unsafe fn join_late(&mut self, pinfo: *mut epan_sys::_packet_info) {
+        let session = self.start_session(pinfo);
+        (*session).joined_late = true;
+        (*session).a = Endpoint::source(pinfo);
+
+        let mut resolution = Resolution::default();
+        resolution.set(
+            Field::FrameSN,
+            sn_bits_from_pref(crate::ASSUMED_SN_RESOLUTION),
+        );
+        let params = &mut (*session).params;
+        params.resolution = resolution.as_u8();
+        params.compression = crate::IS_COMPRESSION;
+        params.lowlatency = crate::FORCE_LOWLATENCY;
+    }
+
+    /// Name the sender of this packet after a ZID it carries, if its session doesn't know it
+    /// yet, e.g. a session joined late. The sender's endpoint becomes B if it isn't A.
+    unsafe fn infer_zid(
+        &mut self,
+        pinfo: *mut epan_sys::_packet_info,
+        zid: &ZenohIdProto,
+        whatami: Option<WhatAmI>,
+    ) {
+        let session = self.session(pinfo);
+        if session.is_null() {
+            return;
+        }
+        let session = &mut *session;
+        let (side_zid, side_node) = if session.a.is_source(pinfo) {
+            (&mut session.a_zid, &mut session.a_node)
+        } else if session.b.is_unset() || session.b.is_source(pinfo) {
+            session.b = Endpoint::source(pinfo);
+            (&mut session.b_zid, &mut session.b_node)
+        } else {
+            return;
+        };
+        if side_zid.is_null() {
+            *side_zid = file_scoped_c_str(zid.to_string());
+        }
+        if let (true, Some(whatami)) = (side_node.is_null(), whatami) {
+            *side_node = node_name(zid, whatami);
         }
     }
 }
 
-/// Update the conversation state from a single transport message.
+/// Returns the session this packet belongs to, or null if none.
+unsafe fn find_session(pinfo: *mut epan_sys::_packet_info) -> *mut Session {
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return ptr::null_mut();
+    }
+    (*conv_state).session(pinfo)
+}
+
+/// Update the conversation state from a single message.
 ///
-/// Extracts ZIDs from InitSyn/InitAck messages and stores them in the conversation state.
-pub(crate) unsafe fn update_state(pinfo: *mut epan_sys::_packet_info, msg: &TransportMessage) {
-    // https://github.com/wireshark/wireshark/blob/7f37406d807ac05b72118a5075a405a30de90bb4/epan/conversation.h#L188-L208
-    let conv = epan_sys::find_conversation_pinfo(pinfo, 0);
-    if conv.is_null() {
+/// Every InitSyn starts a new session; the following handshake messages fill in its ZIDs,
+/// endpoints and negotiated parameters, and Close ends it. Messages of a conversation without
+/// any session start one joined late. The state is only built during the first pass, later
+/// passes look sessions up by frame number.
+pub(crate) unsafe fn update_state(pinfo: *mut epan_sys::_packet_info, body: &MessageBody) {
+    if (*(*pinfo).fd).visited() != 0 {
         return;
     }
 
-    fn file_scoped_c_str(s: impl AsRef<[u8]>) -> *mut c_char {
-        let s = CString::new(s.as_ref()).unwrap();
-        unsafe { epan_sys::wmem_strdup(epan_sys::wmem_file_scope(), s.as_ptr()) }
+    let conv_state = ConversationState::with_pinfo(pinfo);
+    if conv_state.is_null() {
+        return;
+    }
+
+    // Any other message than the start of a handshake or a Join (which isn't part of a
+    // session) means the session started before the capture.
+    let starts_session = matches!(
+        body,
+        MessageBody::Transport(TransportMessage {
+            body: TransportBody::InitSyn(_) | TransportBody::InitAck(_) | TransportBody::Join(_)
+        })
+    );
+    if !starts_session && (*conv_state).session(pinfo).is_null() {
+        (*conv_state).join_late(pinfo);
+    }
+
+    let join_lease = match body {
+        MessageBody::Transport(TransportMessage {
+            body: TransportBody::Join(join),
+        }) => Some(join.lease.as_millis() as u64),
+        _ => None,
+    };
+    (*conv_state).track_sender(pinfo, join_lease);
+    for msg in network_messages(body) {
+        (*conv_state).declare(pinfo, msg);
+        (*conv_state).track_request(pinfo, msg);
+        (*conv_state).track_link_states(pinfo, msg);
+    }
+    if let MessageBody::Transport(TransportMessage {
+        body: TransportBody::Fragment(fragment),
+    }) = body
+    {
+        let reassembled = (*conv_state).defragment(pinfo, fragment);
+        if let Some(msg) = reassembled
+            .as_ref()
+            .and_then(|reassembled| reassembled.decode(fragment.reliability))
+        {
+            (*conv_state).declare(pinfo, &msg);
+            (*conv_state).track_request(pinfo, &msg);
+            (*conv_state).track_link_states(pinfo, &msg);
+        }
     }
 
-    match &msg.body {
+    let msg = match body {
+        MessageBody::Transport(msg) => &msg.body,
+        MessageBody::LowLatency(msg) => {
+            if let TransportBodyLowLatency::Close(_) = msg.body {
+                let session = (*conv_state).session(pinfo);
+                if !session.is_null() {
+                    (*session).close(pinfo);
+                }
+            }
+            return;
+        }
+    };
+
+    match msg {
         TransportBody::InitSyn(init_syn) => {
-            let conv_state = ConversationState::with_pinfo(pinfo);
-            if conv_state.is_null() {
-                return;
+            let session = (*conv_state).start_session(pinfo);
+
+            (*session).a_zid = file_scoped_c_str(init_syn.zid.to_string());
+            (*session).a = Endpoint::source(pinfo);
+            (*session).a_node = node_name(&init_syn.zid, init_syn.whatami);
+
+            let params = &mut (*session).params;
+            params.init_syn_frame = (*pinfo).num;
+            params.offer(init_syn);
+            params.initiator_region_name = region_name(&init_syn.ext_region_name);
+        }
+        TransportBody::InitAck(init_ack) => {
+            // An InitAck whose InitSyn wasn't captured (or which answers an already
+            // acknowledged one) starts a session of its own.
+            let mut session = (*conv_state).session(pinfo);
+            if session.is_null() || !(*session).b_zid.is_null() {
+                session = (*conv_state).start_session(pinfo);
             }
 
-            if !(*conv_state).a_zid.is_null() {
-                ws_log::critical!("duplicate InitSyn");
+            (*session).b_zid = file_scoped_c_str(init_ack.zid.to_string());
+            (*session).b = Endpoint::source(pinfo);
+            (*session).b_node = node_name(&init_ack.zid, init_ack.whatami);
+
+            let params = &mut (*session).params;
+            params.init_ack_frame = (*pinfo).num;
+            params.negotiate(init_ack);
+            params.acceptor_region_name = region_name(&init_ack.ext_region_name);
+        }
+        TransportBody::OpenSyn(open_syn) => {
+            let session = (*conv_state).session(pinfo);
+            if session.is_null() || (*session).params.open_syn_frame != 0 {
                 return;
             }
 
-            (*conv_state).a_zid = file_scoped_c_str(init_syn.zid.to_string());
-            (*conv_state).a_port = (*pinfo).srcport as u16;
+            let params = &mut (*session).params;
+            params.open_syn_frame = (*pinfo).num;
+            params.initiator_lease = open_syn.lease.as_millis() as u64;
+            params.initiator_initial_sn = open_syn.initial_sn;
         }
-        TransportBody::InitAck(init_ack) => {
-            let conv_state = ConversationState::with_pinfo(pinfo);
-            if conv_state.is_null() {
+        TransportBody::OpenAck(open_ack) => {
+            let session = (*conv_state).session(pinfo);
+            if session.is_null() || (*session).params.open_ack_frame != 0 {
                 return;
             }
 
-            if !(*conv_state).b_zid.is_null() {
-                ws_log::critical!("duplicate InitAck");
+            (*session).open_ack_ts = (*pinfo).abs_ts;
+            let params = &mut (*session).params;
+            params.open_ack_frame = (*pinfo).num;
+            params.acceptor_lease = open_ack.lease.as_millis() as u64;
+            params.acceptor_initial_sn = open_ack.initial_sn;
+        }
+        TransportBody::Close(_) => {
+            let session = (*conv_state).session(pinfo);
+            if !session.is_null() {
+                (*session).close(pinfo);
+            }
+        }
+        TransportBody::Join(join) => {
+            (*conv_state).infer_zid(pinfo, &join.zid, Some(join.whatami));
+        }
+        TransportBody::Frame(Frame { sn, .. }) | TransportBody::Fragment(Fragment { sn, .. }) => {
+            let session = (*conv_state).session(pinfo);
+            if session.is_null()
+                || (*session).acceptor_first_sn_frame != 0
+                || (*session).params.open_ack_frame == 0
+                || !(*session).is_from_acceptor(pinfo)
+            {
                 return;
             }
 
-            (*conv_state).b_zid = file_scoped_c_str(init_ack.zid.to_string());
-            (*conv_state).b_port = (*pinfo).srcport as u16;
+            (*session).acceptor_first_sn_frame = (*pinfo).num;
+            (*session).acceptor_first_sn = *sn;
         }
         _ => {}
     }
 }
 
-/// Add Source/Destination ZID fields to the protocol subtree and update the
-/// protocol item text to include them (e.g. "Zenoh Protocol, Src ZID: …, Dst ZID: …").
-///
-/// Called once per frame from `dissect_zenoh_tcp`, after all batches have been processed
-/// so that InitSyn/InitAck in any batch have had a chance to update the conversation state.
-pub(crate) unsafe fn update_tree(
-    tvb: *mut epan_sys::tvbuff,
+/// ZIDs and nodes (as named by [`node_name`]) of both ends of a packet's session, each null if
+/// not known.
+pub(crate) struct SessionPeers {
+    pub src_zid: *const c_char,
+    pub dst_zid: *const c_char,
+    pub src: *const c_char,
+    pub dst: *const c_char,
+}
+
+/// Returns the ZIDs and nodes of both ends of this packet's session.
+pub(crate) unsafe fn session_peers(pinfo: *mut epan_sys::_packet_info) -> SessionPeers {
+    let mut peers = SessionPeers {
+        src_zid: ptr::null(),
+        dst_zid: ptr::null(),
+        src: ptr::null(),
+        dst: ptr::null(),
+    };
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return peers;
+    }
+    let session = (*conv_state).session(pinfo);
+    if session.is_null() {
+        return peers;
+    }
+
+    let (src, dst) = (*session).nodes(pinfo);
+    peers.src_zid = (*session).source(pinfo).unwrap_or(ptr::null());
+    peers.dst_zid = (*session).destination(pinfo).unwrap_or(ptr::null());
+    peers.src = src.unwrap_or(ptr::null());
+    peers.dst = dst.unwrap_or(ptr::null());
+    peers
+}
+
+/// A Request, as matched by its responses.
+#[repr(C)]
+pub(crate) struct RequestInfo {
+    pub frame: u32,
+    pub ts: epan_sys::nstime_t,
+    /// Resolved key expression of the Request.
+    pub keyexpr: *const c_char,
+}
+
+/// Returns the Request a Response or ResponseFinal of this packet answers, i.e. the latest
+/// Request with this id sent by the other side up to this frame, or null if not captured.
+pub(crate) unsafe fn find_request(
     pinfo: *mut epan_sys::_packet_info,
-    tree: *mut epan_sys::_proto_node,
-    proto_item: *mut epan_sys::_proto_node,
+    rid: RequestId,
+) -> *const RequestInfo {
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return ptr::null();
+    }
+    let Some(sender) = (*conv_state).sender(pinfo) else {
+        return ptr::null();
+    };
+    lookup_at_frame((*conv_state).peers[1 - sender].requests, rid, (*pinfo).num) as _
+}
+
+/// Returns the ZID of the node a link state of this packet refers to by `psid`, as carried by a
+/// previous link state of the sender, or `None` if not captured.
+pub(crate) unsafe fn resolve_psid(pinfo: *mut epan_sys::_packet_info, psid: u64) -> Option<String> {
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return None;
+    }
+    let sender = (*conv_state).sender(pinfo)?;
+    let zid = lookup_at_frame((*conv_state).peers[sender].psids, psid as u32, (*pinfo).num)
+        as *const c_char;
+    (!zid.is_null()).then(|| CStr::from_ptr(zid).to_string_lossy().into_owned())
+}
+
+/// Returns the network message completed by a Fragment of this packet, or null if it isn't
+/// the last Fragment of a message or earlier ones weren't captured.
+pub(crate) unsafe fn find_reassembled(
+    pinfo: *mut epan_sys::_packet_info,
+    fragment: &Fragment,
+) -> *const Reassembled {
+    let proto = PROTOCOL_DATA.with_borrow(|data| data.id);
+    let list = epan_sys::p_get_proto_data(
+        epan_sys::wmem_file_scope(),
+        pinfo,
+        proto,
+        PROTO_DATA_KEY_REASSEMBLED,
+    ) as *mut epan_sys::wmem_list_t;
+    if list.is_null() {
+        return ptr::null();
+    }
+
+    let channel = fragment_channel(fragment);
+    let mut frame = epan_sys::wmem_list_head(list);
+    while !frame.is_null() {
+        let reassembled = epan_sys::wmem_list_frame_data(frame) as *const Reassembled;
+        if (*reassembled).channel == channel && (*reassembled).sn == fragment.sn {
+            return reassembled;
+        }
+        frame = epan_sys::wmem_list_frame_next(frame);
+    }
+    ptr::null()
+}
+
+/// Returns the index of this packet's session, if any. Sessions are indexed from 0 in order of
+/// appearance, which identifies them as streams to follow.
+pub(crate) unsafe fn session_index(pinfo: *mut epan_sys::_packet_info) -> Option<u32> {
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return None;
+    }
+    let session = (*conv_state).session(pinfo);
+    (!session.is_null()).then(|| (*session).index)
+}
+
+/// Returns whether this packet carries the first Close of its session.
+pub(crate) unsafe fn closes_session(pinfo: *mut epan_sys::_packet_info) -> bool {
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return false;
+    }
+    let session = (*conv_state).session(pinfo);
+    !session.is_null() && (*session).close_frame == (*pinfo).num
+}
+
+/// Returns the number of sessions started in the capture so far.
+pub(crate) unsafe fn session_count() -> u32 {
+    SESSION_COUNT
+}
+
+/// Forget the sessions of the previous capture, registered as an init routine.
+pub(crate) unsafe extern "C" fn reset_sessions() {
+    SESSION_COUNT = 0;
+}
+
+/// Inserts `value` in a tree of trees keyed by `id`, then by frame number.
+unsafe fn insert_at_frame(tree: *mut epan_sys::wmem_tree_t, id: u32, num: u32, value: *mut c_void) {
+    let mut by_frame = epan_sys::wmem_tree_lookup32(tree, id);
+    if by_frame.is_null() {
+        by_frame = epan_sys::wmem_tree_new(epan_sys::wmem_file_scope()) as _;
+        epan_sys::wmem_tree_insert32(tree, id, by_frame);
+    }
+    epan_sys::wmem_tree_insert32(by_frame as _, num, value);
+}
+
+/// Returns the latest value inserted by [`insert_at_frame`] for `id` at or before frame `num`.
+unsafe fn lookup_at_frame(tree: *mut epan_sys::wmem_tree_t, id: u32, num: u32) -> *mut c_void {
+    let by_frame = epan_sys::wmem_tree_lookup32(tree, id);
+    if by_frame.is_null() {
+        return ptr::null_mut();
+    }
+    epan_sys::wmem_tree_lookup32_le(by_frame as _, num)
+}
+
+/// Returns the network messages carried by a message, i.e. the payload of a Frame or a
+/// low-latency Network message.
+pub(crate) fn network_messages(body: &MessageBody) -> &[NetworkMessage] {
+    match body {
+        MessageBody::Transport(TransportMessage {
+            body: TransportBody::Frame(frame),
+        }) => &frame.payload,
+        MessageBody::LowLatency(TransportMessageLowLatency {
+            body: TransportBodyLowLatency::Network(msg),
+        }) => slice::from_ref(msg),
+        _ => &[],
+    }
+}
+
+/// Resolves a wire expression sent in this packet to its full key expression, or `None` if
+/// its scope wasn't declared in the capture.
+pub(crate) unsafe fn resolve_keyexpr(
+    pinfo: *mut epan_sys::_packet_info,
+    expr: &WireExpr,
+) -> Option<String> {
+    if expr.scope == 0 {
+        return Some(expr.suffix.to_string());
+    }
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return None;
+    }
+    (*conv_state).resolve(pinfo, expr)
+}
+
+/// Validate a message against the lifecycle of its session and flag any inconsistency as
+/// expert info on the message.
+pub(crate) unsafe fn check_message(
+    pinfo: *mut epan_sys::_packet_info,
+    args: &TreeArgs,
+    body: &MessageBody,
 ) {
+    let session = find_session(pinfo);
+    if session.is_null() {
+        return;
+    }
+    let session = &*session;
+    let params = &session.params;
+    let num = (*pinfo).num;
+    let add = |ei, text: &str| {
+        expert::add_expert(
+            args.tree,
+            pinfo,
+            ei,
+            args.tvb,
+            args.start,
+            args.length,
+            text,
+        )
+    };
+
+    // A new session starts with every InitSyn, so it can't come after the Close. The other
+    // peer may still reply with its own Close.
+    let is_close = match body {
+        MessageBody::Transport(msg) => matches!(msg.body, TransportBody::Close(_)),
+        MessageBody::LowLatency(msg) => matches!(msg.body, TransportBodyLowLatency::Close(_)),
+    };
+    let is_close_reply = is_close && session.a.is_source(pinfo) != session.closed_by_a;
+    if session.close_frame != 0 && num > session.close_frame && !is_close_reply {
+        add(
+            &raw mut expert::EI_AFTER_CLOSE,
+            &format!("Message after the Close in frame {}", session.close_frame),
+        );
+    }
+
+    // Messages which may only be exchanged once the session is open.
+    let is_data = match body {
+        MessageBody::Transport(msg) => match &msg.body {
+            TransportBody::InitSyn(_) => false,
+            TransportBody::InitAck(_) => {
+                if params.init_syn_frame == 0 {
+                    add(
+                        &raw mut expert::EI_HANDSHAKE_ORDER,
+                        "InitAck without a preceding InitSyn",
+                    );
+                }
+                false
+            }
+            TransportBody::OpenSyn(_) => {
+                if params.init_ack_frame == 0 || params.init_ack_frame > num {
+                    add(
+                        &raw mut expert::EI_HANDSHAKE_ORDER,
+                        "OpenSyn without a preceding InitAck",
+                    );
+                } else if params.open_syn_frame != num {
+                    add(
+                        &raw mut expert::EI_HANDSHAKE_ORDER,
+                        &format!(
+                            "Duplicate OpenSyn, first in frame {}",
+                            params.open_syn_frame
+                        ),
+                    );
+                }
+                false
+            }
+            TransportBody::OpenAck(open_ack) => {
+                if params.open_syn_frame == 0 || params.open_syn_frame > num {
+                    add(
+                        &raw mut expert::EI_HANDSHAKE_ORDER,
+                        "OpenAck without a preceding OpenSyn",
+                    );
+                } else if params.open_ack_frame != num {
+                    add(
+                        &raw mut expert::EI_HANDSHAKE_ORDER,
+                        &format!(
+                            "Duplicate OpenAck, first in frame {}",
+                            params.open_ack_frame
+                        ),
+                    );
+                }
+                if params.init_ack_frame != 0 {
+                    let bits = Resolution::from(params.resolution).get(Field::FrameSN);
+                    if open_ack.initial_sn as u64 > bits.mask() {
+                        add(
+                            &raw mut expert::EI_INITIAL_SN,
+                            &format!(
+                                "OpenAck initial SN {} exceeds the {bits} FrameSN resolution",
+                                open_ack.initial_sn
+                            ),
+                        );
+                    }
+                }
+                false
+            }
+            TransportBody::Frame(Frame { sn, .. })
+            | TransportBody::Fragment(Fragment { sn, .. }) => {
+                if num == session.acceptor_first_sn_frame
+                    && *sn == session.acceptor_first_sn
+                    && *sn != params.acceptor_initial_sn
+                    && session.is_from_acceptor(pinfo)
+                {
+                    add(
+                        &raw mut expert::EI_INITIAL_SN,
+                        &format!(
+                            "First SN {sn} of the acceptor differs from the OpenAck initial SN {}",
+                            params.acceptor_initial_sn
+                        ),
+                    );
+                }
+                true
+            }
+            TransportBody::KeepAlive(_) => true,
+            _ => false,
+        },
+        MessageBody::LowLatency(msg) => !matches!(msg.body, TransportBodyLowLatency::Close(_)),
+    };
+
+    if is_data
+        && !session.joined_late
+        && (params.open_ack_frame == 0 || params.open_ack_frame > num)
+    {
+        add(
+            &raw mut expert::EI_DATA_BEFORE_OPEN,
+            "Message before the OpenAck of the session",
+        );
+    }
+}
+
+impl FrameTiming {
+    /// Returns the timing recorded for this frame, or null if its sender wasn't seen before.
+    unsafe fn get(pinfo: *mut epan_sys::_packet_info) -> *const FrameTiming {
+        let proto = PROTOCOL_DATA.with_borrow(|data| data.id);
+        epan_sys::p_get_proto_data(
+            epan_sys::wmem_file_scope(),
+            pinfo,
+            proto,
+            PROTO_DATA_KEY_TIMING,
+        ) as *const FrameTiming
+    }
+
+    /// Returns the time elapsed since the previous message of the sender in milliseconds, or
+    /// `None` if that message came later, e.g. in out-of-order or merged captures.
+    fn since_prev_ms(&self) -> Option<u64> {
+        let secs = u64::try_from(self.since_prev.secs).ok()?;
+        let nsecs = u64::try_from(self.since_prev.nsecs).ok()?;
+        Some(secs * 1000 + nsecs / 1_000_000)
+    }
+
+    /// Add the "Timing" subtree of generated fields.
+    unsafe fn add_to_tree(&self, args: &TreeArgs) {
+        let ms_to_nstime = |ms: u64| epan_sys::nstime_t {
+            secs: (ms / 1000) as _,
+            nsecs: ((ms % 1000) * 1_000_000) as _,
+        };
+
+        PROTOCOL_DATA.with_borrow(|data| {
+            let hf = |key: &str| data.hf_map[&format!("{TIMING_PREFIX}.{key}")];
+
+            let ti = epan_sys::proto_tree_add_item(
+                args.tree,
+                data.hf_map[TIMING_PREFIX],
+                args.tvb,
+                args.start as _,
+                0,
+                epan_sys::ENC_NA,
+            );
+            set_generated(ti);
+            let tree = epan_sys::proto_item_add_subtree(ti, data.st_map[TIMING_PREFIX]);
+
+            set_generated(epan_sys::proto_tree_add_uint(
+                tree,
+                hf("prev_frame"),
+                args.tvb,
+                0,
+                0,
+                self.prev_frame,
+            ));
+            set_generated(epan_sys::proto_tree_add_time(
+                tree,
+                hf("since_prev"),
+                args.tvb,
+                0,
+                0,
+                &self.since_prev,
+            ));
+            if self.lease != 0 {
+                set_generated(epan_sys::proto_tree_add_time(
+                    tree,
+                    hf("lease"),
+                    args.tvb,
+                    0,
+                    0,
+                    &ms_to_nstime(self.lease),
+                ));
+                set_generated(epan_sys::proto_tree_add_time(
+                    tree,
+                    hf("keep_alive"),
+                    args.tvb,
+                    0,
+                    0,
+                    &ms_to_nstime(self.lease / KEEP_ALIVE_FACTOR),
+                ));
+            }
+        });
+    }
+
+    /// Flag a sender that stayed silent for longer than its lease, or long enough to have
+    /// missed KeepAlives.
+    unsafe fn check(
+        &self,
+        tvb: *mut epan_sys::tvbuff,
+        pinfo: *mut epan_sys::_packet_info,
+        tree: *mut epan_sys::_proto_node,
+    ) {
+        if self.lease == 0 {
+            return;
+        }
+        // Out-of-order messages say nothing about the silence of their sender.
+        let Some(silence) = self.since_prev_ms() else {
+            return;
+        };
+        if silence > self.lease {
+            expert::add_expert(
+                tree,
+                pinfo,
+                &raw mut expert::EI_LEASE_EXPIRED,
+                tvb,
+                0,
+                0,
+                &format!(
+                    "Sender silent for {silence} ms since frame {}, longer than its lease of {} ms",
+                    self.prev_frame, self.lease
+                ),
+            );
+        } else if silence > 2 * (self.lease / KEEP_ALIVE_FACTOR) {
+            expert::add_expert(
+                tree,
+                pinfo,
+                &raw mut expert::EI_KEEPALIVE_MISSED,
+                tvb,
+                0,
+                0,
+                &format!(
+                    "Sender silent for {silence} ms since frame {}, KeepAlives expected every {} ms",
+                    self.prev_frame,
+                    self.lease / KEEP_ALIVE_FACTOR
+                ),
+            );
+        }
+    }
+}
+
+impl Registration for FrameTiming {
+    fn generate_hf_map(prefix: &str) -> HeaderFieldMap {
+        [
+            ("prev_frame", "Previous Message Frame", FieldKind::FrameNum),
+            (
+                "since_prev",
+                "Time Since Previous Message",
+                FieldKind::Duration,
+            ),
+            ("lease", "Sender Lease", FieldKind::Duration),
+            ("keep_alive", "KeepAlive Interval", FieldKind::Duration),
+        ]
+        .into_iter()
+        .fold(
+            HeaderFieldMap::new().add(prefix.to_string(), "Timing", FieldKind::Branch),
+            |hf_map, (key, name, kind)| hf_map.add(format!("{prefix}.{key}"), name, kind),
+        )
+    }
+
+    fn generate_subtree_names(prefix: &str) -> Vec<String> {
+        vec![prefix.to_string()]
+    }
+}
+
+/// Add the timing of the sender to KeepAlive messages.
+pub(crate) unsafe fn add_keepalive_timing(
+    pinfo: *mut epan_sys::_packet_info,
+    args: &TreeArgs,
+    body: &MessageBody,
+) {
+    let is_keepalive = match body {
+        MessageBody::Transport(msg) => matches!(msg.body, TransportBody::KeepAlive(_)),
+        MessageBody::LowLatency(msg) => matches!(msg.body, TransportBodyLowLatency::KeepAlive(_)),
+    };
+    if !is_keepalive {
+        return;
+    }
+
+    let timing = FrameTiming::get(pinfo);
+    if !timing.is_null() {
+        (*timing).add_to_tree(args);
+    }
+}
+
+/// Identity of the TCP tap listener, see [`tcp_teardown_packet`].
+static mut TCP_TEARDOWN_TAP: u8 = 0;
+
+/// Whether [`TCP_TEARDOWN_TAP`] listens to the TCP tap.
+static mut TCP_TEARDOWN_TRACKING: bool = false;
+
+/// Start following TCP teardowns once a session was closed over TCP, so that captures without
+/// any don't pay for a listener of every TCP packet. Stopped by [`stop_teardown_tracking`].
+unsafe fn track_teardown(pinfo: *mut epan_sys::_packet_info) {
+    if TCP_TEARDOWN_TRACKING || (*pinfo).ptype != epan_sys::port_type_PT_TCP {
+        return;
+    }
+    let error = epan_sys::register_tap_listener(
+        c"tcp".as_ptr(),
+        &raw mut TCP_TEARDOWN_TAP as _,
+        ptr::null(),
+        epan_sys::TL_REQUIRES_NOTHING,
+        None,
+        Some(tcp_teardown_packet),
+        None,
+        None,
+    );
+    if !error.is_null() {
+        ws_log::critical!("failed to register the TCP teardown listener");
+        epan_sys::g_string_free(error, 1);
+        return;
+    }
+    TCP_TEARDOWN_TRACKING = true;
+}
+
+/// Stop following TCP teardowns when the capture is closed, registered as a cleanup routine.
+pub(crate) unsafe extern "C" fn stop_teardown_tracking() {
+    if TCP_TEARDOWN_TRACKING {
+        epan_sys::remove_tap_listener(&raw mut TCP_TEARDOWN_TAP as _);
+        TCP_TEARDOWN_TRACKING = false;
+    }
+}
+
+/// Listener of the TCP tap linking the Close of a session to the FIN or RST that follows it on
+/// the same stream, see [`track_teardown`].
+pub(crate) unsafe extern "C" fn tcp_teardown_packet(
+    _tapdata: *mut std::ffi::c_void,
+    pinfo: *mut epan_sys::_packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const std::ffi::c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let tcph = data as *const epan_sys::tcpheader;
+    if tcph.is_null()
+        || (*(*pinfo).fd).visited() != 0
+        || (*tcph).th_flags as u32 & (epan_sys::TH_FIN | epan_sys::TH_RST) == 0
+    {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    }
+
+    let conv_state = ConversationState::find(pinfo);
+    if conv_state.is_null() {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    }
+
+    let session = (*conv_state).session(pinfo);
+    if !session.is_null()
+        && (*session).close_frame != 0
+        && (*session).close_frame <= (*pinfo).num
+        && (*session).teardown_frame == 0
+    {
+        (*session).teardown_frame = (*pinfo).num;
+    }
+
+    epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW
+}
+
+/// Values of the preference of the FrameSN resolution assumed for sessions joined late,
+/// terminated by a null entry as Wireshark expects.
+pub(crate) static mut SN_RESOLUTION_PREF_VALUES: [epan_sys::enum_val_t; 5] = [
+    epan_sys::enum_val_t {
+        name: c"8bit".as_ptr(),
+        description: c"8 bits".as_ptr(),
+        value: Bits::U8 as _,
+    },
+    epan_sys::enum_val_t {
+        name: c"16bit".as_ptr(),
+        description: c"16 bits".as_ptr(),
+        value: Bits::U16 as _,
+    },
+    epan_sys::enum_val_t {
+        name: c"32bit".as_ptr(),
+        description: c"32 bits".as_ptr(),
+        value: Bits::U32 as _,
+    },
+    epan_sys::enum_val_t {
+        name: c"64bit".as_ptr(),
+        description: c"64 bits".as_ptr(),
+        value: Bits::U64 as _,
+    },
+    epan_sys::enum_val_t {
+        name: ptr::null(),
+        description: ptr::null(),
+        value: 0,
+    },
+];
+
+/// Returns the resolution of a value of [`SN_RESOLUTION_PREF_VALUES`].
+fn sn_bits_from_pref(value: c_int) -> Bits {
+    [Bits::U8, Bits::U16, Bits::U64]
+        .into_iter()
+        .find(|&bits| bits as c_int == value)
+        .unwrap_or(Bits::U32)
+}
+
+/// Bytes skipped at the start of a stream segment to reach a batch boundary, stored per frame
+/// during the first pass.
+#[derive(Debug)]
+#[repr(C)]
+struct Resync {
+    /// Length of the data handed to the dissector, which tells the segment apart from a
+    /// reassembled PDU of the same frame.
+    len: u32,
+    skipped: u32,
+}
+
+/// Returns whether the stream of the sender of this packet is known to be on a batch boundary,
+/// i.e. one of its batches was decoded.
+pub(crate) unsafe fn is_synced(pinfo: *mut epan_sys::_packet_info) -> bool {
+    let conv_state = ConversationState::find(pinfo);
+    !conv_state.is_null()
+        && (*conv_state)
+            .peer_mut(pinfo)
+            .is_some_and(|peer| peer.synced)
+}
+
+/// Returns whether Zenoh was already decoded on the conversation of this packet.
+pub(crate) unsafe fn is_tracked(pinfo: *mut epan_sys::_packet_info) -> bool {
+    !ConversationState::find(pinfo).is_null()
+}
+
+/// Record that a batch of the stream of the sender of this packet was decoded.
+pub(crate) unsafe fn mark_synced(pinfo: *mut epan_sys::_packet_info) {
+    if (*(*pinfo).fd).visited() != 0 {
+        return;
+    }
     let conv_state = ConversationState::with_pinfo(pinfo);
     if conv_state.is_null() {
         return;
     }
+    if let Some(peer) = (*conv_state).peer_mut(pinfo) {
+        peer.synced = true;
+    }
+}
+
+/// Record that the first `skipped` bytes of the `len` bytes handed to the dissector for this
+/// frame precede the first batch boundary.
+pub(crate) unsafe fn record_resync(pinfo: *mut epan_sys::_packet_info, len: usize, skipped: usize) {
+    let proto = PROTOCOL_DATA.with_borrow(|data| data.id);
+    let scope = epan_sys::wmem_file_scope();
+    if !epan_sys::p_get_proto_data(scope, pinfo, proto, PROTO_DATA_KEY_RESYNC).is_null() {
+        return;
+    }
+    let resync = epan_sys::wmem_alloc0(scope, mem::size_of::<Resync>()) as *mut Resync;
+    resync.write(Resync {
+        len: len as _,
+        skipped: skipped as _,
+    });
+    epan_sys::p_add_proto_data(scope, pinfo, proto, PROTO_DATA_KEY_RESYNC, resync as _);
+}
+
+/// Returns the bytes recorded by [`record_resync`] for these `len` bytes of this frame, 0 if
+/// none.
+pub(crate) unsafe fn recorded_resync(pinfo: *mut epan_sys::_packet_info, len: usize) -> usize {
+    let proto = PROTOCOL_DATA.with_borrow(|data| data.id);
+    let resync = epan_sys::p_get_proto_data(
+        epan_sys::wmem_file_scope(),
+        pinfo,
+        proto,
+        PROTO_DATA_KEY_RESYNC,
+    ) as *const Resync;
+    match resync.as_ref() {
+        Some(resync) if resync.len as usize == len => resync.skipped as _,
+        _ => 0,
+    }
+}
+
+/// Link configuration negotiated by a session.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LinkConfig {
+    /// Batches carry a header and may be compressed.
+    pub compression: bool,
+    /// The link carries low-latency messages instead of batches.
+    pub lowlatency: bool,
+}
+
+/// Returns the link configuration of this packet according to the session's negotiation, or
+/// `None` if the handshake wasn't captured.
+///
+/// The handshake itself (up to and including the OpenAck frame) always uses the default
+/// configuration.
+pub(crate) unsafe fn link_config(pinfo: *mut epan_sys::_packet_info) -> Option<LinkConfig> {
+    let session = find_session(pinfo);
+    if session.is_null() || (*session).params.init_syn_frame == 0 {
+        return None;
+    }
 
-    if let Some(src) = (*conv_state).source(pinfo) {
+    let params = &(*session).params;
+    if params.open_ack_frame == 0 || (*pinfo).num <= params.open_ack_frame {
+        return Some(LinkConfig::default());
+    }
+
+    Some(LinkConfig {
+        compression: params.compression,
+        lowlatency: params.lowlatency,
+    })
+}
+
+/// Add Source/Destination ZID fields to the protocol subtree and return their summary for the
+/// protocol item (e.g. ", Src ZID: …, Dst ZID: …"). Frames of a session also get its generated
+/// "Session parameters" subtree.
+///
+/// Called once per frame, after the batches of the frame have been processed so that
+/// InitSyn/InitAck in any batch have had a chance to update the conversation state.
+pub(crate) unsafe fn update_tree(
+    tvb: *mut epan_sys::tvbuff,
+    pinfo: *mut epan_sys::_packet_info,
+    tree: *mut epan_sys::_proto_node,
+) -> String {
+    let timing = FrameTiming::get(pinfo);
+    if !timing.is_null() {
+        (*timing).check(tvb, pinfo, tree);
+    }
+
+    let session = find_session(pinfo);
+    if session.is_null() {
+        return String::new();
+    }
+
+    let mut text = String::new();
+    if let Some(src) = (*session).source(pinfo) {
         epan_sys::proto_tree_add_string(
             tree,
             PROTOCOL_DATA.with_borrow(|d| d.hf_map[FIELD_SRCZID]),
@@ -163,15 +2056,10 @@ pub(crate) unsafe fn update_tree(
             0,
             src,
         );
-        let text = CString::new(format!(
-            ", Src ZID: {}",
-            CStr::from_ptr(src).to_str().unwrap()
-        ))
-        .unwrap();
-        epan_sys::proto_item_append_text(proto_item, text.as_ptr());
+        text += &format!(", Src ZID: {}", CStr::from_ptr(src).to_str().unwrap());
     }
 
-    if let Some(dst) = (*conv_state).destination(pinfo) {
+    if let Some(dst) = (*session).destination(pinfo) {
         epan_sys::proto_tree_add_string(
             tree,
             PROTOCOL_DATA.with_borrow(|d| d.hf_map[FIELD_DSTZID]),
@@ -180,11 +2068,168 @@ pub(crate) unsafe fn update_tree(
             0,
             dst,
         );
-        let text = CString::new(format!(
-            ", Dst ZID: {}",
-            CStr::from_ptr(dst).to_str().unwrap()
-        ))
-        .unwrap();
-        epan_sys::proto_item_append_text(proto_item, text.as_ptr());
+        text += &format!(", Dst ZID: {}", CStr::from_ptr(dst).to_str().unwrap());
+    }
+
+    // Nodes name a ZID with its WhatAmI. They back the Zenoh conversation and endpoint tables,
+    // hence the hidden `zenoh.node` matching either side.
+    let (src, dst) = (*session).nodes(pinfo);
+    for (key, node) in [(FIELD_SRC, src), (FIELD_DST, dst)] {
+        let Some(node) = node else {
+            continue;
+        };
+        let ti = epan_sys::proto_tree_add_string(
+            tree,
+            PROTOCOL_DATA.with_borrow(|d| d.hf_map[key]),
+            tvb,
+            0,
+            0,
+            node,
+        );
+        set_generated(ti);
+        let ti = epan_sys::proto_tree_add_string(
+            tree,
+            PROTOCOL_DATA.with_borrow(|d| d.hf_map[FIELD_NODE]),
+            tvb,
+            0,
+            0,
+            node,
+        );
+        set_generated(ti);
+        set_hidden(ti);
+    }
+
+    (*session).add_to_tree(tvb, pinfo, tree);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use init::{ext, InitAck, InitSyn};
+    use zenoh_buffers::ZSlice;
+
+    fn init_syn(batch_size: u16, patch: u8) -> InitSyn {
+        InitSyn {
+            version: zenoh_protocol::VERSION,
+            whatami: WhatAmI::Peer,
+            zid: ZenohIdProto::default(),
+            resolution: Resolution::default(),
+            batch_size,
+            ext_qos: None,
+            ext_qos_link: None,
+            ext_shm: None,
+            ext_auth: None,
+            ext_mlink: None,
+            ext_lowlatency: None,
+            ext_compression: None,
+            ext_patch: ext::PatchType::new(patch),
+            ext_region_name: None,
+        }
+    }
+
+    fn init_ack(batch_size: u16, patch: u8) -> InitAck {
+        InitAck {
+            version: zenoh_protocol::VERSION,
+            whatami: WhatAmI::Router,
+            zid: ZenohIdProto::default(),
+            resolution: Resolution::default(),
+            batch_size,
+            cookie: ZSlice::from(vec![0]),
+            ext_qos: None,
+            ext_qos_link: None,
+            ext_shm: None,
+            ext_auth: None,
+            ext_mlink: None,
+            ext_lowlatency: None,
+            ext_compression: None,
+            ext_patch: ext::PatchType::new(patch),
+            ext_region_name: None,
+        }
+    }
+
+    fn negotiate(init_syn: &InitSyn, init_ack: &InitAck) -> SessionParams {
+        let mut params = SessionParams::new();
+        params.offer(init_syn);
+        params.negotiate(init_ack);
+        params
+    }
+
+    #[test]
+    fn negotiation_takes_the_minimum() {
+        let mut ack = init_ack(8192, 0);
+        ack.resolution.set(Field::FrameSN, Bits::U16);
+        let params = negotiate(&init_syn(65535, 1), &ack);
+        assert_eq!(params.syn_batch_size, 65535);
+        assert_eq!(params.batch_size, 8192);
+        assert_eq!(params.syn_patch, 1);
+        assert_eq!(params.patch, 0);
+        assert_eq!(
+            Resolution::from(params.resolution).get(Field::FrameSN),
+            Bits::U16
+        );
+    }
+
+    #[test]
+    fn negotiation_needs_both_sides() {
+        let mut syn = init_syn(1024, 1);
+        syn.ext_lowlatency = Some(ext::LowLatency::new());
+        syn.ext_compression = Some(ext::Compression::new());
+        let params = negotiate(&syn, &init_ack(1024, 1));
+        assert!(params.syn_lowlatency && !params.lowlatency);
+        assert!(params.syn_compression && !params.compression);
+
+        let mut ack = init_ack(1024, 1);
+        ack.ext_lowlatency = Some(ext::LowLatency::new());
+        ack.ext_compression = Some(ext::Compression::new());
+        let params = negotiate(&init_syn(1024, 1), &ack);
+        assert!(!params.lowlatency && !params.compression);
+        let params = negotiate(&syn, &ack);
+        assert!(params.lowlatency && params.compression);
+    }
+
+    #[test]
+    fn negotiation_of_qos() {
+        let mut syn = init_syn(1024, 1);
+        syn.ext_qos_link = Some(ext::QoSLink::new(7));
+        let mut ack = init_ack(1024, 1);
+        ack.ext_qos = Some(ext::QoS::new());
+        let params = negotiate(&syn, &ack);
+        assert!(params.qos);
+        assert!(!params.has_qos_link);
+
+        ack.ext_qos_link = Some(ext::QoSLink::new(3));
+        let params = negotiate(&syn, &ack);
+        assert!(params.qos && params.has_qos_link);
+        assert_eq!(params.qos_link, 3);
+
+        let params = negotiate(&init_syn(1024, 1), &ack);
+        assert!(!params.qos && !params.has_qos_link);
+    }
+
+    #[test]
+    fn since_prev_ms() {
+        let timing = |secs, nsecs| FrameTiming {
+            prev_frame: 1,
+            since_prev: epan_sys::nstime_t { secs, nsecs },
+            lease: 0,
+        };
+        assert_eq!(timing(0, 0).since_prev_ms(), Some(0));
+        assert_eq!(timing(2, 345_678_901).since_prev_ms(), Some(2345));
+        // The previous message came later, e.g. in a merged capture.
+        assert_eq!(timing(-1, 500_000_000).since_prev_ms(), None);
+        assert_eq!(timing(0, -500_000_000).since_prev_ms(), None);
+    }
+
+    #[test]
+    fn sn_bits_from_prefs() {
+        let values = unsafe { &*ptr::addr_of!(SN_RESOLUTION_PREF_VALUES) };
+        let bits: Vec<_> = values
+            .iter()
+            .take_while(|value| !value.name.is_null())
+            .map(|value| sn_bits_from_pref(value.value))
+            .collect();
+        assert_eq!(bits, [Bits::U8, Bits::U16, Bits::U32, Bits::U64]);
+        assert_eq!(sn_bits_from_pref(-1), Bits::U32);
     }
 }
@@ -0,0 +1,133 @@
+use crate::utils::leak_nul_terminated_str;
+use anyhow::Result;
+use epan_sys::expert_field;
+use std::ffi::CString;
+
+const UNREGISTERED: expert_field = expert_field { ei: -1, hf: -1 };
+
+pub static mut EI_HANDSHAKE_ORDER: expert_field = UNREGISTERED;
+pub static mut EI_DATA_BEFORE_OPEN: expert_field = UNREGISTERED;
+pub static mut EI_AFTER_CLOSE: expert_field = UNREGISTERED;
+pub static mut EI_INITIAL_SN: expert_field = UNREGISTERED;
+pub static mut EI_LEASE_EXPIRED: expert_field = UNREGISTERED;
+pub static mut EI_KEEPALIVE_MISSED: expert_field = UNREGISTERED;
+pub static mut EI_RESYNC: expert_field = UNREGISTERED;
+
+/// Register the expert info fields of the protocol.
+pub fn register_expert_fields(proto_id: i32) -> Result<()> {
+    let fields = [
+        (
+            &raw mut EI_HANDSHAKE_ORDER,
+            "zenoh.expert.handshake_order",
+            "Handshake message out of order",
+            epan_sys::PI_SEQUENCE,
+            epan_sys::PI_WARN,
+        ),
+        (
+            &raw mut EI_DATA_BEFORE_OPEN,
+            "zenoh.expert.data_before_open",
+            "Message sent before the session was opened",
+            epan_sys::PI_SEQUENCE,
+            epan_sys::PI_WARN,
+        ),
+        (
+            &raw mut EI_AFTER_CLOSE,
+            "zenoh.expert.after_close",
+            "Message sent after the session was closed",
+            epan_sys::PI_SEQUENCE,
+            epan_sys::PI_WARN,
+        ),
+        (
+            &raw mut EI_INITIAL_SN,
+            "zenoh.expert.initial_sn",
+            "Inconsistent initial sequence number",
+            epan_sys::PI_PROTOCOL,
+            epan_sys::PI_WARN,
+        ),
+        (
+            &raw mut EI_LEASE_EXPIRED,
+            "zenoh.expert.lease_expired",
+            "Sender silent for longer than its lease",
+            epan_sys::PI_SEQUENCE,
+            epan_sys::PI_WARN,
+        ),
+        (
+            &raw mut EI_KEEPALIVE_MISSED,
+            "zenoh.expert.keepalive_missed",
+            "Sender silent for longer than two KeepAlive intervals",
+            epan_sys::PI_SEQUENCE,
+            epan_sys::PI_NOTE,
+        ),
+        (
+            &raw mut EI_RESYNC,
+            "zenoh.expert.resync",
+            "Bytes skipped to resynchronise on a batch boundary",
+            epan_sys::PI_SEQUENCE,
+            epan_sys::PI_NOTE,
+        ),
+    ];
+
+    let mut infos = Vec::with_capacity(fields.len());
+    for (ids, name, summary, group, severity) in fields {
+        infos.push(epan_sys::ei_register_info {
+            ids,
+            // Strings are leaked for the same reason as in `register_header_field`.
+            eiinfo: epan_sys::expert_field_info {
+                name: leak_nul_terminated_str(name)?,
+                group: group as _,
+                severity: severity as _,
+                summary: leak_nul_terminated_str(summary)?,
+                id: 0,
+                protocol: std::ptr::null(),
+                orig_severity: 0,
+                hf_info: epan_sys::hf_register_info {
+                    p_id: std::ptr::null_mut(),
+                    hfinfo: epan_sys::header_field_info {
+                        name: std::ptr::null(),
+                        abbrev: std::ptr::null(),
+                        type_: epan_sys::ftenum_FT_NONE,
+                        display: epan_sys::field_display_e_BASE_NONE as _,
+                        strings: std::ptr::null(),
+                        bitmask: 0,
+                        blurb: std::ptr::null(),
+                        id: -1,
+                        parent: 0,
+                        ref_type: epan_sys::hf_ref_type_HF_REF_TYPE_NONE,
+                        same_name_prev_id: -1,
+                        same_name_next: std::ptr::null_mut(),
+                    },
+                },
+            },
+        });
+    }
+    let infos = infos.leak();
+
+    unsafe {
+        let module = epan_sys::expert_register_protocol(proto_id);
+        epan_sys::expert_register_field_array(module, infos.as_mut_ptr(), infos.len() as _);
+    }
+    Ok(())
+}
+
+/// Add an expert info item covering `start..start + length` of `tvb` to `tree`.
+pub unsafe fn add_expert(
+    tree: *mut epan_sys::proto_tree,
+    pinfo: *mut epan_sys::_packet_info,
+    ei: *mut expert_field,
+    tvb: *mut epan_sys::tvbuff,
+    start: usize,
+    length: usize,
+    text: &str,
+) {
+    let text = CString::new(text).unwrap();
+    epan_sys::proto_tree_add_expert_format(
+        tree,
+        pinfo,
+        ei,
+        tvb,
+        start as _,
+        length as _,
+        c"%s".as_ptr(),
+        text.as_ptr(),
+    );
+}
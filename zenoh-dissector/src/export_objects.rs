@@ -0,0 +1,153 @@
+use crate::{
+    conversation,
+    tap::message_data,
+    utils::{encoding_extension, encoding_name},
+    MessageBody,
+};
+use std::ffi::{c_char, c_int, c_void, CString};
+use zenoh_buffers::buffer::SplitBuffer;
+use zenoh_protocol::{
+    network::{NetworkBody, NetworkMessage},
+    zenoh::{PushBody, ResponseBody},
+};
+
+/// Tap of File > Export Objects > Zenoh, fed with the [`Object`]s of each message only while the
+/// dialog is open, so that payloads are only copied for it.
+static mut EXPORT_TAP_ID: c_int = -1;
+
+/// Register File > Export Objects > Zenoh, listing the payloads of Put and Reply messages,
+/// including the ones reassembled from Fragments.
+pub fn register_export_objects(proto_id: i32) {
+    unsafe {
+        EXPORT_TAP_ID = epan_sys::register_export_object(proto_id, Some(export_packet), None);
+    }
+}
+
+/// The payload of a Put or Reply, with its resolved key expression and encoding.
+struct Object {
+    keyexpr: String,
+    encoding: String,
+    payload: Vec<u8>,
+}
+
+/// Queue the payloads of the Put and Reply messages carried by a message, or completed by a
+/// Fragment, to the export objects tap, if anyone listens to it.
+pub unsafe fn queue_objects(
+    pinfo: *mut epan_sys::_packet_info,
+    body: &MessageBody,
+    reassembled: Option<&NetworkMessage>,
+) {
+    if !epan_sys::have_tap_listener(EXPORT_TAP_ID) {
+        return;
+    }
+
+    let msgs = conversation::network_messages(body)
+        .iter()
+        .chain(reassembled);
+    for msg in msgs.filter(|msg| is_object(&msg.body)) {
+        let Some(data) = message_data(&msg.body) else {
+            continue;
+        };
+        let (Some(payload), Some(encoding)) = (data.payload, data.encoding) else {
+            continue;
+        };
+        let object = Object {
+            keyexpr: conversation::resolve_keyexpr(pinfo, data.wire_expr)
+                .unwrap_or_else(|| data.wire_expr.to_string()),
+            encoding: encoding_name(encoding),
+            payload: payload.contiguous().into_owned(),
+        };
+
+        // Taps run after the dissection of the packet, so the object is dropped with its scope.
+        unsafe extern "C" fn drop_object(
+            _allocator: *mut epan_sys::wmem_allocator_t,
+            _event: epan_sys::wmem_cb_event_t,
+            user_data: *mut c_void,
+        ) -> bool {
+            std::ptr::drop_in_place(user_data as *mut Object);
+            false
+        }
+        let ptr = epan_sys::wmem_alloc((*pinfo).pool, std::mem::size_of::<Object>()) as *mut Object;
+        ptr.write(object);
+        epan_sys::wmem_register_callback((*pinfo).pool, Some(drop_object), ptr as _);
+        epan_sys::tap_queue_packet(EXPORT_TAP_ID, pinfo, ptr as *const _);
+    }
+}
+
+/// Returns whether a network message is a Put or a Reply.
+fn is_object(body: &NetworkBody) -> bool {
+    match body {
+        NetworkBody::Push(push) => matches!(push.payload, PushBody::Put(_)),
+        NetworkBody::Response(response) => matches!(response.payload, ResponseBody::Reply(_)),
+        _ => false,
+    }
+}
+
+/// Returns a file name for a payload from its key expression, e.g. `robot_1_camera.png` for a
+/// `image/png` payload of `robot/1/camera`.
+fn file_name(keyexpr: &str, encoding: &str) -> String {
+    let stem: String = keyexpr
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = if stem.is_empty() { "payload" } else { &stem };
+    format!("{stem}.{}", encoding_extension(encoding))
+}
+
+/// Returns a copy of a string allocated with GLib, as export object entries are freed with it.
+unsafe fn g_string(s: &str) -> *mut c_char {
+    let s = CString::new(s.replace('\0', "")).unwrap();
+    epan_sys::g_strdup(s.as_ptr())
+}
+
+unsafe extern "C" fn export_packet(
+    tapdata: *mut c_void,
+    pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let object = &*(data as *const Object);
+
+    let entry = epan_sys::g_malloc0(std::mem::size_of::<epan_sys::export_object_entry_t>() as _)
+        as *mut epan_sys::export_object_entry_t;
+    (*entry).pkt_num = (*pinfo).num;
+    // Key expressions are shown in the Hostname column, as they tell where a payload comes from.
+    (*entry).hostname = g_string(&object.keyexpr);
+    (*entry).content_type = g_string(&object.encoding);
+    (*entry).filename = g_string(&file_name(&object.keyexpr, &object.encoding));
+    (*entry).payload_len = object.payload.len() as _;
+    (*entry).payload_data =
+        epan_sys::g_memdup2(object.payload.as_ptr() as _, object.payload.len() as _) as *mut u8;
+
+    let object_list = tapdata as *mut epan_sys::export_object_list_t;
+    if let Some(add_entry) = (*object_list).add_entry {
+        add_entry((*object_list).gui_data, entry);
+    }
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names() {
+        assert_eq!(
+            file_name("robot/1/camera", "image/png"),
+            "robot_1_camera.png"
+        );
+        assert_eq!(
+            file_name("a/**/b-c.d", "text/plain;utf-8"),
+            "a____b-c.d.txt"
+        );
+        assert_eq!(file_name("ключ/x", "unknown(9999)"), "_____x.bin");
+        assert_eq!(file_name("", "application/json"), "payload.json");
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    stats::PRIORITY_NAMES,
+    tap::{TapRecord, TAP_NAME},
+};
+use std::ffi::{c_char, c_void, CStr, CString};
+
+/// Register the Zenoh flows of Statistics > Flow Graph, drawn from the `zenoh` tap with one
+/// arrow per message between the nodes of its session.
+pub fn register_flow_graph(proto_id: i32) {
+    unsafe {
+        epan_sys::register_seq_analysis(
+            c"zenoh".as_ptr(),
+            c"Zenoh Flows".as_ptr(),
+            proto_id,
+            TAP_NAME.as_ptr(),
+            epan_sys::TL_REQUIRES_NOTHING,
+            Some(flow_packet),
+        );
+    }
+}
+
+/// Returns the label of the arrow of a record, or `None` if the record isn't drawn: Frames and
+/// Fragments are drawn as the network messages they carry.
+unsafe fn label(record: &TapRecord) -> Option<String> {
+    if record.network.is_null() {
+        let transport = CStr::from_ptr(record.transport);
+        if transport == c"Frame" || transport == c"Fragment" {
+            return None;
+        }
+        return Some(transport.to_string_lossy().into_owned());
+    }
+
+    let mut label = CStr::from_ptr(record.network)
+        .to_string_lossy()
+        .into_owned();
+    if !record.keyexpr.is_null() {
+        label += &format!("({})", CStr::from_ptr(record.keyexpr).to_string_lossy());
+    }
+    if record.has_request_id {
+        label += &format!(" #{}", record.request_id);
+    }
+    Some(label)
+}
+
+/// Returns the comment of a record: the zenoh message and payload length, then the QoS.
+unsafe fn comment(record: &TapRecord) -> String {
+    let mut comment = Vec::new();
+    if !record.zenoh.is_null() {
+        comment.push(format!(
+            "{} ({} B)",
+            CStr::from_ptr(record.zenoh).to_string_lossy(),
+            record.payload_len
+        ));
+    }
+    if !record.reliability.is_null() {
+        comment.push(CStr::from_ptr(record.reliability).to_string_lossy().into());
+    }
+    if let Some(priority) = usize::try_from(record.priority)
+        .ok()
+        .and_then(|p| PRIORITY_NAMES.get(p))
+    {
+        comment.push(priority.to_string_lossy().into());
+    }
+    if record.express {
+        comment.push("Express".into());
+    }
+    if record.network.is_null() {
+        comment.push(format!("{} B", record.len));
+    }
+    comment.join(", ")
+}
+
+/// Replace an address of a flow graph item by a node, freed along with the item.
+unsafe fn set_node(addr: &mut epan_sys::address, node: *const c_char) {
+    epan_sys::g_free(addr.priv_);
+    let node = epan_sys::g_strdup(node);
+    *addr = epan_sys::address {
+        type_: epan_sys::address_type_AT_STRINGZ as _,
+        len: CStr::from_ptr(node).to_bytes_with_nul().len() as _,
+        data: node as _,
+        priv_: node as _,
+    };
+}
+
+unsafe extern "C" fn flow_packet(
+    tapdata: *mut c_void,
+    pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let record = &*(data as *const TapRecord);
+    let Some(label) = label(record) else {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    };
+
+    let sainfo = tapdata as *mut epan_sys::seq_analysis_info_t;
+    let sai = epan_sys::sequence_analysis_create_sai_with_addresses(pinfo, sainfo);
+    if sai.is_null() {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    }
+
+    // Sessions whose handshake was captured are drawn between their nodes, the others between
+    // the addresses of the link.
+    if !record.src.is_null() && !record.dst.is_null() {
+        set_node(&mut (*sai).src_addr, record.src);
+        set_node(&mut (*sai).dst_addr, record.dst);
+    }
+
+    let label = CString::new(label.replace('\0', "")).unwrap();
+    let comment = CString::new(comment(record).replace('\0', "")).unwrap();
+    (*sai).frame_label = epan_sys::g_strdup(label.as_ptr());
+    (*sai).comment = epan_sys::g_strdup(comment.as_ptr());
+    epan_sys::sequence_analysis_use_color_filter(pinfo, sai);
+    epan_sys::g_queue_push_tail((*sainfo).items, sai as _);
+
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
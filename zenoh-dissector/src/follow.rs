@@ -0,0 +1,213 @@
+use crate::{
+    conversation,
+    tap::{message_data, network_name, zenoh_name},
+    utils::{lowlatency_message_summary, transport_message_summary},
+    MessageBody,
+};
+use std::ffi::{c_char, c_int, c_uint, CStr, CString};
+use zenoh_buffers::{
+    buffer::{Buffer, SplitBuffer},
+    ZBuf,
+};
+use zenoh_protocol::{
+    core::Reliability,
+    network::{NetworkMessage, NetworkMessageExt},
+    transport::{TransportBody, TransportBodyLowLatency},
+};
+
+/// Name of the tap feeding Follow > Zenoh Session with one line of text per message.
+const FOLLOW_TAP_NAME: &CStr = c"zenoh_follow";
+
+static mut FOLLOW_TAP_ID: c_int = -1;
+
+/// Number of payload bytes shown in the log.
+const PAYLOAD_PREVIEW_LEN: usize = 64;
+
+/// Register Follow > Zenoh Session, which follows the session of a packet (see
+/// `zenoh.session.index`) as a chronological log of its decoded messages.
+pub fn register_follow(proto_id: i32) {
+    unsafe {
+        FOLLOW_TAP_ID = epan_sys::register_tap(FOLLOW_TAP_NAME.as_ptr());
+        epan_sys::register_follow_stream(
+            proto_id,
+            FOLLOW_TAP_NAME.as_ptr(),
+            Some(conv_filter),
+            Some(index_filter),
+            Some(address_filter),
+            Some(port_to_display),
+            Some(epan_sys::follow_tvb_tap_listener),
+            Some(stream_count),
+            None,
+        );
+    }
+}
+
+/// Queue the log lines of a message to the follow tap, if anyone listens to it. Frames are
+/// logged as their network messages, and Fragments as the network message they complete.
+pub unsafe fn queue_message(
+    pinfo: *mut epan_sys::_packet_info,
+    tvb: *mut epan_sys::tvbuff,
+    body: &MessageBody,
+    reassembled: Option<&NetworkMessage>,
+) {
+    if !epan_sys::have_tap_listener(FOLLOW_TAP_ID) {
+        return;
+    }
+
+    let prefix = {
+        let rel_ts = &(*pinfo).rel_ts;
+        format!(
+            "[{} @ {}.{:06}s] ",
+            (*pinfo).num,
+            rel_ts.secs,
+            rel_ts.nsecs / 1000
+        )
+    };
+    let lines = match body {
+        MessageBody::Transport(msg) => match &msg.body {
+            TransportBody::Frame(frame) => frame
+                .payload
+                .iter()
+                .map(|msg| network_line(pinfo, msg))
+                .collect(),
+            TransportBody::Fragment(_) => reassembled
+                .map(|msg| network_line(pinfo, msg) + " (reassembled)")
+                .into_iter()
+                .collect(),
+            _ => vec![transport_message_summary(msg)],
+        },
+        MessageBody::LowLatency(msg) => match &msg.body {
+            TransportBodyLowLatency::Network(msg) => vec![network_line(pinfo, msg)],
+            _ => vec![lowlatency_message_summary(msg)],
+        },
+    };
+    if lines.is_empty() {
+        return;
+    }
+
+    let text: String = lines
+        .iter()
+        .map(|line| format!("{prefix}{line}\n"))
+        .collect();
+    let data = epan_sys::wmem_memdup((*pinfo).pool, text.as_ptr() as _, text.len());
+    let text_tvb =
+        epan_sys::tvb_new_child_real_data(tvb, data as _, text.len() as _, text.len() as _);
+    epan_sys::tap_queue_packet(FOLLOW_TAP_ID, pinfo, text_tvb as *const _);
+}
+
+/// Returns the line of a network message: its type, resolved key expression, QoS and a
+/// preview of its payload.
+unsafe fn network_line(pinfo: *mut epan_sys::_packet_info, msg: &NetworkMessage) -> String {
+    let mut line = network_name(&msg.body).to_string_lossy().into_owned();
+    if let Some(zenoh) = zenoh_name(&msg.body) {
+        line += &format!("({})", zenoh.to_string_lossy());
+    }
+
+    let data = message_data(&msg.body);
+    if let Some(data) = &data {
+        let expr = data.wire_expr;
+        let keyexpr =
+            conversation::resolve_keyexpr(pinfo, expr).unwrap_or_else(|| expr.to_string());
+        line += &format!(" {keyexpr}");
+    }
+
+    let reliability = match msg.reliability {
+        Reliability::Reliable => "Reliable",
+        Reliability::BestEffort => "BestEffort",
+    };
+    line += &format!(
+        " {{{reliability}, {:?}, {:?}{}}}",
+        msg.priority(),
+        msg.congestion_control(),
+        if msg.is_express() { ", Express" } else { "" }
+    );
+
+    if let Some(payload) = data.and_then(|data| data.payload) {
+        line += &format!(" {} B: \"{}\"", payload.len(), payload_preview(payload));
+    }
+    line
+}
+
+/// Returns the first bytes of a payload as text, with non-printable bytes shown as dots.
+fn payload_preview(payload: &ZBuf) -> String {
+    let bytes = payload.contiguous();
+    let mut preview: String = bytes
+        .iter()
+        .take(PAYLOAD_PREVIEW_LEN)
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    if bytes.len() > PAYLOAD_PREVIEW_LEN {
+        preview += "...";
+    }
+    preview
+}
+
+/// Returns a string allocated with GLib, as the follow callbacks must.
+unsafe fn g_string(s: String) -> *mut c_char {
+    let s = CString::new(s).unwrap();
+    epan_sys::g_strdup(s.as_ptr())
+}
+
+unsafe extern "C" fn conv_filter(
+    _edt: *mut epan_sys::epan_dissect_t,
+    pinfo: *mut epan_sys::packet_info,
+    stream: *mut c_uint,
+    sub_stream: *mut c_uint,
+) -> *mut c_char {
+    let Some(index) = conversation::session_index(pinfo) else {
+        return std::ptr::null_mut();
+    };
+    *stream = index;
+    *sub_stream = 0;
+    index_filter(index, 0)
+}
+
+unsafe extern "C" fn index_filter(stream: c_uint, _sub_stream: c_uint) -> *mut c_char {
+    g_string(format!("zenoh.session.index eq {stream}"))
+}
+
+/// Filter of the Zenoh traffic between two endpoints, over TCP or UDP.
+unsafe extern "C" fn address_filter(
+    src_addr: *mut epan_sys::address,
+    dst_addr: *mut epan_sys::address,
+    src_port: c_int,
+    dst_port: c_int,
+) -> *mut c_char {
+    let ip = match (*src_addr).type_ as epan_sys::address_type {
+        epan_sys::address_type_AT_IPv6 => "ipv6",
+        _ => "ip",
+    };
+    let to_str = |addr| {
+        let s = epan_sys::address_to_str(std::ptr::null_mut(), addr);
+        let owned = CStr::from_ptr(s).to_string_lossy().into_owned();
+        epan_sys::wmem_free(std::ptr::null_mut(), s as _);
+        owned
+    };
+    let (src, dst) = (to_str(src_addr), to_str(dst_addr));
+    g_string(format!(
+        "zenoh && ((({ip}.src eq {src} && {ip}.dst eq {dst}) && \
+         (tcp.srcport eq {src_port} || udp.srcport eq {src_port}) && \
+         (tcp.dstport eq {dst_port} || udp.dstport eq {dst_port})) || \
+         (({ip}.src eq {dst} && {ip}.dst eq {src}) && \
+         (tcp.srcport eq {dst_port} || udp.srcport eq {dst_port}) && \
+         (tcp.dstport eq {src_port} || udp.dstport eq {src_port})))"
+    ))
+}
+
+unsafe extern "C" fn port_to_display(
+    allocator: *mut epan_sys::wmem_allocator_t,
+    port: c_uint,
+) -> *mut c_char {
+    let port = CString::new(port.to_string()).unwrap();
+    epan_sys::wmem_strdup(allocator, port.as_ptr())
+}
+
+unsafe extern "C" fn stream_count() -> u32 {
+    conversation::session_count()
+}
@@ -57,8 +57,13 @@ impl HeaderFieldMap {
 pub enum FieldKind {
     Text,
     Branch,
-    // Number,
-    // Bytes,
+    Number,
+    Boolean,
+    FrameNum,
+    Duration,
+    /// Unsigned integer displayed with the name of its value, e.g. "Expired (5)".
+    Enum(&'static [(u32, &'static str)]),
+    Bytes,
 }
 
 pub trait Registration {
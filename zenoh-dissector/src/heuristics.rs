@@ -0,0 +1,271 @@
+use crate::{utils::new_rbatch, BATCH_HEADER_LEN};
+use zenoh_protocol::transport::{id, BatchSize, TransportMessage};
+use zenoh_transport::common::batch::Decode;
+
+/// Flags of each transport message id that Zenoh leaves unset, indexed by id.
+const RESERVED_FLAGS: [u8; 8] = [
+    0x00, // OAM, see OAM_ENC_MASK
+    0x00, // Init
+    0x00, // Open
+    0x40, // Close
+    0x60, // KeepAlive
+    0x40, // Frame
+    0x00, // Fragment
+    0x00, // Join
+];
+
+/// Body encoding of OAM messages in bits 5-6, of which 0b11 is unused.
+const OAM_ENC_MASK: u8 = 0x60;
+
+/// Flag of InitAck messages, unset in InitSyn.
+const INIT_ACK_FLAG: u8 = 0x20;
+
+/// Returns whether the first message of a batch payload has a known transport message id, no
+/// reserved flag and, for OAMs, a defined body encoding. InitSyn messages must also carry the
+/// supported protocol version, a valid WhatAmI and a ZID fitting in the batch.
+pub(crate) fn is_plausible_message(payload: &[u8]) -> bool {
+    let Some(&header) = payload.first() else {
+        return false;
+    };
+    let msg_id = header & 0x1f;
+    let Some(reserved) = RESERVED_FLAGS.get(msg_id as usize) else {
+        return false;
+    };
+    if header & reserved != 0 {
+        return false;
+    }
+    if msg_id == id::OAM && header & OAM_ENC_MASK == OAM_ENC_MASK {
+        return false;
+    }
+
+    if msg_id == id::INIT && header & INIT_ACK_FLAG == 0 {
+        let [_, version, whatami_zid, ..] = *payload else {
+            return false;
+        };
+        // WhatAmI in bits 0-1 (0b11 is unused), bits 2-3 unused, ZID length - 1 in bits 4-7.
+        let zid_len = (whatami_zid >> 4) as usize + 1;
+        return version == zenoh_protocol::VERSION
+            && whatami_zid & 0x03 != 0x03
+            && whatami_zid & 0x0c == 0
+            && payload.len() >= 3 + zid_len;
+    }
+    true
+}
+
+/// Returns whether the batch payload starts with a plausible message, after its batch header if
+/// the session uses compression. Compressed batches can't be checked further.
+pub(crate) fn is_plausible_batch(payload: &[u8], compression: bool) -> bool {
+    if !compression {
+        return is_plausible_message(payload);
+    }
+    match payload.first() {
+        Some(0) => is_plausible_message(&payload[1..]),
+        Some(1) => payload.len() > 1,
+        _ => false,
+    }
+}
+
+/// Returns the length of the complete batches at the start of a stream segment, each prefixed
+/// by its length header, or `None` if one of them isn't plausible. The last batch may be
+/// truncated by the end of the segment, in which case only its first message is checked.
+pub(crate) fn plausible_batches_len(segment: &[u8], compression: bool) -> Option<usize> {
+    let mut offset = 0;
+    while offset + BATCH_HEADER_LEN <= segment.len() {
+        let batch_len = u16::from_le_bytes([segment[offset], segment[offset + 1]]) as usize;
+        let start = offset + BATCH_HEADER_LEN;
+        let end = (start + batch_len).min(segment.len());
+        if batch_len == 0 || !is_plausible_batch(&segment[start..end], compression) {
+            return None;
+        }
+        if start + batch_len > segment.len() {
+            break;
+        }
+        offset = start + batch_len;
+    }
+    Some(offset)
+}
+
+/// Returns whether a batch payload decodes into transport messages, to the last byte.
+pub(crate) fn decodes(payload: &[u8], compression: bool) -> bool {
+    let Ok(mut rbatch) = new_rbatch(payload, compression) else {
+        return false;
+    };
+    while !rbatch.is_empty() {
+        let decoded: Result<(TransportMessage, BatchSize), _> = rbatch.decode();
+        if decoded.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns whether a TCP segment, or a UDP datagram if `stream` is false, holds Zenoh batches:
+/// cheap checks of the batch length headers and message headers first, then a full decode of
+/// the complete batches, of which there must be at least one.
+pub(crate) fn is_zenoh(data: &[u8], stream: bool, compression: bool) -> bool {
+    if !stream {
+        return is_plausible_batch(data, compression) && decodes(data, compression);
+    }
+
+    let Some(len) = plausible_batches_len(data, compression) else {
+        return false;
+    };
+    if len == 0 {
+        return false;
+    }
+    let mut offset = 0;
+    while offset < len {
+        let batch_len = u16::from_le_bytes([data[offset], data[offset + 1]]) as usize;
+        let start = offset + BATCH_HEADER_LEN;
+        if !decodes(&data[start..start + batch_len], compression) {
+            return false;
+        }
+        offset = start + batch_len;
+    }
+    true
+}
+
+/// Offsets of a segment scanned for a batch boundary: a batch is never longer than this, so a
+/// segment holding a boundary has one within it.
+const RESYNC_WINDOW: usize = BATCH_HEADER_LEN + BatchSize::MAX as usize;
+
+/// Candidate boundaries of a segment whose batches are fully decoded before giving up on it.
+const MAX_RESYNC_DECODES: usize = 16;
+
+/// Returns the offset of the first batch boundary of a stream segment captured in the middle of
+/// a session, or `None` if there's none: the first offset from which the segment passes
+/// [`is_zenoh`]. Past the start of the segment, its complete batches must also reach its end or
+/// number at least two, as a single short batch is too easily matched by payload bytes.
+///
+/// Offsets are first checked against the batch length and message headers, and only the
+/// first [`MAX_RESYNC_DECODES`] passing them are decoded, so that a segment of payload bytes
+/// is rejected in linear time.
+///
+/// Batches larger than a segment are never matched, so a stream of such batches is only
+/// resynchronised on the next batch fitting in a segment.
+pub(crate) fn find_batch_boundary(segment: &[u8], compression: bool) -> Option<usize> {
+    let mut decodes_left = MAX_RESYNC_DECODES;
+    for offset in 0..segment.len().min(RESYNC_WINDOW) {
+        let rest = &segment[offset..];
+        let Some(len) = plausible_batches_len(rest, compression) else {
+            continue;
+        };
+        if len == 0 || (offset != 0 && len != rest.len() && complete_batch_count(rest) < 2) {
+            continue;
+        }
+        if is_zenoh(rest, true, compression) {
+            return Some(offset);
+        }
+        decodes_left -= 1;
+        if decodes_left == 0 {
+            return None;
+        }
+    }
+    None
+}
+
+/// Returns the number of complete batches at the start of a stream segment, whatever their
+/// content.
+fn complete_batch_count(segment: &[u8]) -> usize {
+    let mut count = 0;
+    let mut offset = 0;
+    while offset + BATCH_HEADER_LEN <= segment.len() {
+        let batch_len = u16::from_le_bytes([segment[offset], segment[offset + 1]]) as usize;
+        offset += BATCH_HEADER_LEN + batch_len;
+        if offset > segment.len() {
+            break;
+        }
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenoh_protocol::transport::id;
+
+    /// A stream batch holding a single KeepAlive.
+    const KEEP_ALIVE_BATCH: [u8; 3] = [1, 0, id::KEEP_ALIVE];
+    /// The end of a batch cut by the start of a segment.
+    const BATCH_END: [u8; 2] = [0x99, 0x33];
+
+    #[test]
+    fn is_zenoh_datagrams() {
+        assert!(is_zenoh(&[id::KEEP_ALIVE], false, false));
+        assert!(is_zenoh(&[0, id::KEEP_ALIVE], false, true));
+        assert!(!is_zenoh(&[], false, false));
+        assert!(!is_zenoh(&[0xff], false, false));
+        // A KeepAlive with a reserved flag.
+        assert!(!is_zenoh(&[id::KEEP_ALIVE | 0x40], false, false));
+    }
+
+    #[test]
+    fn is_zenoh_streams() {
+        assert!(is_zenoh(&KEEP_ALIVE_BATCH.repeat(2), true, false));
+        assert!(is_zenoh(&[2, 0, 0, id::KEEP_ALIVE], true, true));
+        assert!(!is_zenoh(&[], true, false));
+        assert!(!is_zenoh(&[0, 0, id::KEEP_ALIVE], true, false));
+        assert!(!is_zenoh(b"GET / HTTP/1.1\r\n", true, false));
+    }
+
+    #[test]
+    fn is_zenoh_needs_a_complete_batch() {
+        let truncated = [9, 0, id::KEEP_ALIVE];
+        assert!(!is_zenoh(&truncated, true, false));
+        assert!(is_zenoh(
+            &[&KEEP_ALIVE_BATCH[..], &truncated].concat(),
+            true,
+            false
+        ));
+    }
+
+    #[test]
+    fn is_plausible_init_syn() {
+        let init_syn = |version, whatami_zid| [id::INIT, version, whatami_zid, 0x1a];
+        assert!(is_plausible_message(&init_syn(
+            zenoh_protocol::VERSION,
+            0x01
+        )));
+        assert!(!is_plausible_message(&init_syn(0, 0x01)));
+        assert!(!is_plausible_message(&init_syn(
+            zenoh_protocol::VERSION,
+            0x03
+        )));
+        // A ZID of 2 bytes which doesn't fit in the batch.
+        assert!(!is_plausible_message(&init_syn(
+            zenoh_protocol::VERSION,
+            0x11
+        )));
+    }
+
+    #[test]
+    fn find_batch_boundary_at_start() {
+        assert_eq!(find_batch_boundary(&KEEP_ALIVE_BATCH, false), Some(0));
+        let truncated = [&KEEP_ALIVE_BATCH[..], &[9, 0, id::KEEP_ALIVE]].concat();
+        assert_eq!(find_batch_boundary(&truncated, false), Some(0));
+    }
+
+    #[test]
+    fn find_batch_boundary_mid_batch() {
+        let segment = [&BATCH_END[..], &KEEP_ALIVE_BATCH.repeat(2)].concat();
+        assert_eq!(find_batch_boundary(&segment, false), Some(BATCH_END.len()));
+        // A single batch reaching the end of the segment is enough.
+        let segment = [&BATCH_END[..], &KEEP_ALIVE_BATCH].concat();
+        assert_eq!(find_batch_boundary(&segment, false), Some(BATCH_END.len()));
+    }
+
+    #[test]
+    fn find_batch_boundary_rejects_a_single_short_batch_mid_batch() {
+        let segment = [&BATCH_END[..], &KEEP_ALIVE_BATCH, &[9, 0, id::KEEP_ALIVE]].concat();
+        assert_eq!(find_batch_boundary(&segment, false), None);
+    }
+
+    #[test]
+    fn find_batch_boundary_gives_up_on_payload_bytes() {
+        // OAMs without a body: plausible headers which never decode.
+        let segment = [1, 0, id::OAM].repeat(1000);
+        assert_eq!(find_batch_boundary(&segment, false), None);
+        assert_eq!(find_batch_boundary(&[], false), None);
+    }
+}
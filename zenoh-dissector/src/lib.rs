@@ -1,28 +1,59 @@
 use anyhow::Result;
+use conversation::{FrameTiming, Session};
 use header_field::{FieldKind, Registration};
-use std::{cell::RefCell, collections::HashMap, ffi::CString, slice, sync::LazyLock};
+use linkstate::LinkState;
+use std::{cell::RefCell, collections::HashMap, ffi::CString, slice};
 use tree::{AddToTree, TreeArgs};
-use utils::{new_rbatch, transport_message_summary, SizedSummary};
+use utils::{
+    decode_lowlatency, decode_transport, new_rbatch, new_rbatch_with_fallback, SizedSummary,
+};
 use wireshark::register_header_field;
+use zenoh_buffers::{reader::HasReader, ZSlice};
+use zenoh_codec::{RCodec, Zenoh080};
 use zenoh_impl::ZenohProtocol;
-use zenoh_protocol::transport::{BatchSize, TransportMessage};
-use zenoh_transport::common::batch::Decode;
+use zenoh_protocol::{
+    network::NetworkMessage,
+    scouting::{ScoutingBody, ScoutingMessage},
+    transport::{BatchSize, TransportBody, TransportMessage, TransportMessageLowLatency},
+};
+use zenoh_transport::common::batch::{Decode, RBatch};
 
 mod conversation;
+mod expert;
+mod export_objects;
+mod flow;
+mod follow;
 mod header_field;
+mod heuristics;
+mod linkstate;
 mod macros;
+mod oam;
+mod srt;
+mod stats;
+mod summary;
+mod tables;
+mod tap;
+mod topology;
 mod tree;
 mod utils;
 mod wireshark;
 mod ws_log;
 mod zenoh_impl;
 
-/// Max number of message summaries in one batch.
-const MAX_BATCH_SUMMARY: usize = 5;
-/// Max length of a single message summary string.
-const MSG_SUMMARY_LIMIT: usize = 30;
-/// Length of the batch size header prepended to each Zenoh batch in TCP streams.
+/// Length of the batch size header prepended to each Zenoh batch in stream links.
 const BATCH_HEADER_LEN: usize = 2;
+/// Flag of compressed batches in the batch header of sessions with compression.
+const BATCH_HEADER_COMPRESSION: u8 = 0x01;
+/// Length of the size header prepended to each low-latency message in stream links.
+const LOWLATENCY_HEADER_LEN: usize = 4;
+/// Default vsock port of Zenoh `vsock` locators.
+const VSOCK_PORT: u32 = 7447;
+/// Default ports of Zenoh `tcp`, `tls`, `quic` and `ws` locators.
+const DEFAULT_PORTS: &std::ffi::CStr = c"7447";
+/// Default ports of Zenoh `udp` locators and of the scouting protocol.
+const DEFAULT_UDP_PORTS: &std::ffi::CStr = c"7446-7447";
+/// Port of the Zenoh scouting protocol (Scout and Hello messages).
+const SCOUTING_PORT: u32 = 7446;
 
 // Version symbols are generated at build time from Cargo.toml metadata
 include!(concat!(env!("OUT_DIR"), "/version.rs"));
@@ -42,11 +73,18 @@ thread_local! {
 }
 
 // Global variables for interacting wtih wireshark preference
-static mut IS_COMPRESSION: bool = false;
-static mut UDP_PORT: u32 = 7447;
-static mut TCP_PORT: u32 = 7447;
-static mut CURR_UDP_PORT: u32 = 7447;
-static mut CURR_TCP_PORT: u32 = 7447;
+pub(crate) static mut IS_COMPRESSION: bool = false;
+pub(crate) static mut FORCE_LOWLATENCY: bool = false;
+// FrameSN resolution of sessions whose handshake wasn't captured, see `conversation::join_late`.
+pub(crate) static mut ASSUMED_SN_RESOLUTION: std::ffi::c_int =
+    zenoh_protocol::core::resolution::Bits::U32 as _;
+// QUIC carries Zenoh through heuristics, as it has no port table of its own.
+static mut QUIC_PORTS: *mut epan_sys::range_t = std::ptr::null_mut();
+static mut INFO_VERBOSITY: std::ffi::c_int = summary::Verbosity::Full as _;
+// Max number of message summaries in the Info column, 0 for no limit.
+static mut INFO_MAX_MESSAGES: u32 = 5;
+// Max length of a single message summary in the Info column, 0 for no limit.
+static mut INFO_MAX_LENGTH: u32 = 60;
 
 #[no_mangle]
 extern "C" fn plugin_register() {
@@ -60,42 +98,13 @@ extern "C" fn plugin_register() {
         PLUG.register_handoff = Some(register_handoff);
         epan_sys::proto_register_plugin(&raw const PLUG);
     }
-}
-
-#[no_mangle]
-unsafe extern "C" fn prefs_callback() {
-    if CURR_TCP_PORT != TCP_PORT {
-        #[allow(static_mut_refs)] // Wireshark requires these references to be static mut
-        {
-            ws_log::message!("Update TCP Port: {CURR_TCP_PORT} -> {TCP_PORT}");
-        }
-        PROTOCOL_DATA.with(|data| {
-            let handle = data
-                .borrow()
-                .handle
-                .expect("Handle after registration shouldn't be empty");
-            let tcp_keyword = c"tcp.port".as_ptr();
-            epan_sys::dissector_delete_uint(tcp_keyword, CURR_TCP_PORT, handle);
-            epan_sys::dissector_add_uint_with_preference(tcp_keyword, TCP_PORT as _, handle);
-        });
-        CURR_TCP_PORT = TCP_PORT;
-    }
 
-    if CURR_UDP_PORT != UDP_PORT {
-        #[allow(static_mut_refs)] // Wireshark requires these references to be static mut
-        {
-            ws_log::message!("Update UDP Port: {CURR_UDP_PORT} -> {UDP_PORT}");
-        }
-        PROTOCOL_DATA.with(|data| {
-            let handle = data
-                .borrow()
-                .handle
-                .expect("Handle after registration shouldn't be empty");
-            let udp_keyword = c"udp.port".as_ptr();
-            epan_sys::dissector_delete_uint(udp_keyword, CURR_UDP_PORT, handle);
-            epan_sys::dissector_add_uint_with_preference(udp_keyword, UDP_PORT as _, handle);
-        });
-        CURR_UDP_PORT = UDP_PORT;
+    // Statistics trees are registered with the tap plugins, once all taps exist.
+    static TAP_PLUG: epan_sys::tap_plugin = epan_sys::tap_plugin {
+        register_tap_listener: Some(stats::register_stats_trees),
+    };
+    unsafe {
+        epan_sys::tap_register_plugin(&raw const TAP_PLUG);
     }
 }
 
@@ -110,34 +119,85 @@ fn register_zenoh_protocol() -> Result<()> {
 
     // Register preferences for the zenoh protocol
     unsafe {
-        let zenoh_module = epan_sys::prefs_register_protocol(proto_id, Some(prefs_callback));
-        epan_sys::prefs_register_uint_preference(
-            zenoh_module,
-            c"tcp.port".as_ptr(),
-            c"TCP Port".as_ptr(),
-            c"Zenoh TCP Port to listen to".as_ptr(),
-            10 as _,
-            &raw mut TCP_PORT as _,
+        // The TCP, UDP, TLS and WebSocket port ranges are registered along with the handoffs.
+        let zenoh_module = epan_sys::prefs_register_protocol(proto_id, None);
+        epan_sys::range_convert_str(
+            epan_sys::wmem_epan_scope(),
+            &raw mut QUIC_PORTS,
+            DEFAULT_PORTS.as_ptr(),
+            u16::MAX as _,
         );
-        epan_sys::prefs_register_uint_preference(
+        epan_sys::prefs_register_range_preference(
             zenoh_module,
-            c"udp.port".as_ptr(),
-            c"UDP Port".as_ptr(),
-            c"Zenoh UDP Port to listen to".as_ptr(),
-            10 as _,
-            &raw mut UDP_PORT as _,
+            c"quic.port".as_ptr(),
+            c"QUIC port(s)".as_ptr(),
+            c"QUIC ports of Zenoh quic locators".as_ptr(),
+            &raw mut QUIC_PORTS,
+            u16::MAX as _,
         );
         epan_sys::prefs_register_bool_preference(
             zenoh_module,
             c"is_compression".as_ptr(),
             c"Is Compression".as_ptr(),
-            c"Is Zenoh message compressed".as_ptr(),
+            c"Is Zenoh message compressed (only for sessions whose handshake wasn't captured)"
+                .as_ptr(),
             &raw mut IS_COMPRESSION as _,
         );
+        epan_sys::prefs_register_bool_preference(
+            zenoh_module,
+            c"force_lowlatency".as_ptr(),
+            c"Force Low Latency".as_ptr(),
+            c"Decode sessions whose handshake wasn't captured as low-latency transport".as_ptr(),
+            &raw mut FORCE_LOWLATENCY as _,
+        );
+        epan_sys::prefs_register_enum_preference(
+            zenoh_module,
+            c"assumed_sn_resolution".as_ptr(),
+            c"Assumed FrameSN Resolution".as_ptr(),
+            c"FrameSN resolution of sessions whose handshake wasn't captured".as_ptr(),
+            &raw mut ASSUMED_SN_RESOLUTION,
+            (&raw const conversation::SN_RESOLUTION_PREF_VALUES).cast(),
+            false,
+        );
+        epan_sys::prefs_register_enum_preference(
+            zenoh_module,
+            c"info_verbosity".as_ptr(),
+            c"Info Column Verbosity".as_ptr(),
+            c"Details of the message summaries shown in the Info column".as_ptr(),
+            &raw mut INFO_VERBOSITY,
+            (&raw const summary::VERBOSITY_PREF_VALUES).cast(),
+            false,
+        );
+        epan_sys::prefs_register_uint_preference(
+            zenoh_module,
+            c"info_max_messages".as_ptr(),
+            c"Info Column Max Messages".as_ptr(),
+            c"Max number of message summaries in the Info column (0 for no limit)".as_ptr(),
+            10 as _,
+            &raw mut INFO_MAX_MESSAGES as _,
+        );
+        epan_sys::prefs_register_uint_preference(
+            zenoh_module,
+            c"info_max_length".as_ptr(),
+            c"Info Column Max Length".as_ptr(),
+            c"Max length of a message summary in the Info column (0 for no limit)".as_ptr(),
+            10 as _,
+            &raw mut INFO_MAX_LENGTH as _,
+        );
     }
 
-    let hf_map = ZenohProtocol::generate_hf_map("zenoh");
-    let subtree_names = ZenohProtocol::generate_subtree_names("zenoh");
+    let mut hf_map = ZenohProtocol::generate_hf_map("zenoh");
+    hf_map.extend(Session::generate_hf_map(conversation::SESSION_PREFIX));
+    hf_map.extend(FrameTiming::generate_hf_map(conversation::TIMING_PREFIX));
+    hf_map.extend(LinkState::generate_hf_map(linkstate::PREFIX));
+    let mut subtree_names = ZenohProtocol::generate_subtree_names("zenoh");
+    subtree_names.extend(Session::generate_subtree_names(
+        conversation::SESSION_PREFIX,
+    ));
+    subtree_names.extend(FrameTiming::generate_subtree_names(
+        conversation::TIMING_PREFIX,
+    ));
+    subtree_names.extend(LinkState::generate_subtree_names(linkstate::PREFIX));
 
     PROTOCOL_DATA.with(|data| {
         data.borrow_mut().id = proto_id;
@@ -170,6 +230,17 @@ fn register_zenoh_protocol() -> Result<()> {
             )?,
         );
 
+        for (key, name) in [
+            (conversation::FIELD_SRC, "Source Node"),
+            (conversation::FIELD_DST, "Destination Node"),
+            (conversation::FIELD_NODE, "Node"),
+        ] {
+            data.borrow_mut().hf_map.insert(
+                key.to_string(),
+                register_header_field(proto_id, name, key, FieldKind::Text)?,
+            );
+        }
+
         // Subtree
         for name in subtree_names {
             // Create a raw pointer to ETT (Epan Tree Type) by
@@ -191,6 +262,19 @@ fn register_zenoh_protocol() -> Result<()> {
 
         anyhow::Ok(())
     })?;
+
+    expert::register_expert_fields(proto_id)?;
+    tap::register_tap();
+    topology::register_tap();
+    tables::register_tables(proto_id);
+    srt::register_srt(proto_id);
+    follow::register_follow(proto_id);
+    flow::register_flow_graph(proto_id);
+    export_objects::register_export_objects(proto_id);
+    unsafe {
+        epan_sys::register_init_routine(Some(conversation::reset_sessions));
+        epan_sys::register_cleanup_routine(Some(conversation::stop_teardown_tracking));
+    }
     Ok(())
 }
 
@@ -204,11 +288,78 @@ unsafe extern "C" fn register_handoff() {
     PROTOCOL_DATA.with(|data| {
         let proto_id = data.borrow().id;
 
+        // Each port range comes with a preference of the same name, e.g. `zenoh.tcp.port`.
         let handle = epan_sys::create_dissector_handle(Some(dissect_zenoh), proto_id);
-        epan_sys::dissector_add_uint_with_preference(c"tcp.port".as_ptr(), TCP_PORT as _, handle);
-        epan_sys::dissector_add_uint_with_preference(c"udp.port".as_ptr(), UDP_PORT as _, handle);
+        epan_sys::dissector_add_uint_range_with_preference(
+            c"tcp.port".as_ptr(),
+            DEFAULT_PORTS.as_ptr(),
+            handle,
+        );
+        epan_sys::dissector_add_uint_range_with_preference(
+            c"udp.port".as_ptr(),
+            DEFAULT_UDP_PORTS.as_ptr(),
+            handle,
+        );
         data.borrow_mut().handle = Some(handle);
 
+        // Stream links other than TCP (unixsock-stream, vsock) carry the same length-prefixed
+        // batches. The named dissector can be selected in the User DLTs table or referenced by
+        // exported PDUs.
+        let stream_handle = epan_sys::register_dissector(
+            c"zenoh_stream".as_ptr(),
+            Some(dissect_zenoh_tcp),
+            proto_id,
+        );
+
+        // TLS and QUIC links carry the same stream of batches once decrypted, WebSocket links
+        // carry a batch per message. See `carrier_handle` for how they are told apart from
+        // plain TCP and UDP links sharing their ports.
+        epan_sys::dissector_add_uint_range_with_preference(
+            c"tls.port".as_ptr(),
+            DEFAULT_PORTS.as_ptr(),
+            stream_handle,
+        );
+        if !epan_sys::find_dissector_table(c"ws.port".as_ptr()).is_null() {
+            let ws_handle = epan_sys::create_dissector_handle(Some(dissect_zenoh_udp), proto_id);
+            epan_sys::dissector_add_uint_range_with_preference(
+                c"ws.port".as_ptr(),
+                DEFAULT_PORTS.as_ptr(),
+                ws_handle,
+            );
+        }
+        if epan_sys::has_heur_dissector_list(c"quic".as_ptr()) {
+            epan_sys::heur_dissector_add(
+                c"quic".as_ptr(),
+                Some(dissect_zenoh_quic_heur),
+                c"Zenoh over QUIC".as_ptr(),
+                c"zenoh_quic".as_ptr(),
+                proto_id,
+                epan_sys::heuristic_enable_e_HEURISTIC_ENABLE,
+            );
+        }
+
+        // Depending on the Wireshark build, the vsock dissector hands its payload over either
+        // by port or through heuristics.
+        if !epan_sys::find_dissector_table(c"vsock.port".as_ptr()).is_null() {
+            epan_sys::dissector_add_uint_with_preference(
+                c"vsock.port".as_ptr(),
+                VSOCK_PORT,
+                stream_handle,
+            );
+            ws_log::message!("Zenoh stream dissector is registered for vsock port {VSOCK_PORT}");
+        }
+        if epan_sys::has_heur_dissector_list(c"vsock".as_ptr()) {
+            epan_sys::heur_dissector_add(
+                c"vsock".as_ptr(),
+                Some(dissect_zenoh_stream_heur),
+                c"Zenoh over vsock (heuristic)".as_ptr(),
+                c"zenoh_vsock_heur".as_ptr(),
+                proto_id,
+                epan_sys::heuristic_enable_e_HEURISTIC_DISABLE,
+            );
+            ws_log::message!("Zenoh heuristic dissector is registered for vsock");
+        }
+
         // See https://www.wireshark.org/docs/wsar_html/group__packet.html#gac1f89fb22ed3dd53cb3aecbc7b87a528
         epan_sys::heur_dissector_add(
             c"tcp".as_ptr(),
@@ -227,12 +378,11 @@ unsafe extern "C" fn register_handoff() {
             epan_sys::heuristic_enable_e_HEURISTIC_DISABLE,
         );
 
-        #[allow(static_mut_refs)] // Wireshark requires these references to be static mut
-        {
-            ws_log::message!(
-                "Zenoh dissector is registered for TCP port {TCP_PORT} and UDP port {UDP_PORT}"
-            );
-        }
+        ws_log::message!(
+            "Zenoh dissector is registered for TCP, TLS, QUIC and WebSocket ports {} and UDP ports {}",
+            DEFAULT_PORTS.to_string_lossy(),
+            DEFAULT_UDP_PORTS.to_string_lossy()
+        );
         ws_log::message!("Zenoh heuristic dissector is registered for TCP and UDP");
     });
 }
@@ -243,7 +393,71 @@ unsafe extern "C" fn dissect_zenoh_heur(
     tree: *mut epan_sys::_proto_node,
     data: *mut std::ffi::c_void,
 ) -> bool {
-    dissect_zenoh(tvb, pinfo, tree, data) != 0
+    let stream = (*pinfo).ptype == epan_sys::port_type_PT_TCP;
+    if !is_zenoh_heur(tvb, stream) {
+        return false;
+    }
+    if dissect_zenoh(tvb, pinfo, tree, data) == 0 {
+        return false;
+    }
+    let handle = PROTOCOL_DATA.with_borrow(|d| d.handle.expect("dissector handle not registered"));
+    pin_conversation(pinfo, handle);
+    true
+}
+
+unsafe extern "C" fn dissect_zenoh_stream_heur(
+    tvb: *mut epan_sys::tvbuff,
+    pinfo: *mut epan_sys::_packet_info,
+    tree: *mut epan_sys::_proto_node,
+    data: *mut std::ffi::c_void,
+) -> bool {
+    if !is_zenoh_heur(tvb, true) {
+        return false;
+    }
+    if dissect_zenoh_tcp(tvb, pinfo, tree, data) == 0 {
+        return false;
+    }
+    pin_conversation(pinfo, epan_sys::find_dissector(c"zenoh_stream".as_ptr()));
+    true
+}
+
+/// Returns whether the heuristic dissectors should claim a segment, or a datagram if `stream`
+/// is false, see [`heuristics::is_zenoh`]. Handshake batches are never compressed, so
+/// compression is only assumed as a fallback if the preference enables it.
+unsafe fn is_zenoh_heur(tvb: *mut epan_sys::tvbuff, stream: bool) -> bool {
+    let len = epan_sys::tvb_captured_length(tvb) as usize;
+    if len == 0 {
+        return false;
+    }
+    let data = slice::from_raw_parts(epan_sys::tvb_get_ptr(tvb, 0, len as _), len);
+    heuristics::is_zenoh(data, stream, false)
+        || (IS_COMPRESSION && heuristics::is_zenoh(data, stream, true))
+}
+
+/// Dissect the rest of the conversation of a packet claimed by a heuristic dissector with
+/// `handle`, skipping the heuristics. Only called once the packet was dissected.
+unsafe fn pin_conversation(
+    pinfo: *mut epan_sys::_packet_info,
+    handle: epan_sys::dissector_handle_t,
+) {
+    epan_sys::conversation_set_dissector(epan_sys::find_or_create_conversation(pinfo), handle);
+}
+
+/// Dissect Zenoh over QUIC streams whose connection uses a port of the QUIC range, once their
+/// data passes the same checks as the other heuristic dissectors.
+unsafe extern "C" fn dissect_zenoh_quic_heur(
+    tvb: *mut epan_sys::tvbuff,
+    pinfo: *mut epan_sys::_packet_info,
+    tree: *mut epan_sys::_proto_node,
+    data: *mut std::ffi::c_void,
+) -> bool {
+    let is_zenoh_port = |port| epan_sys::value_is_in_range(QUIC_PORTS, port);
+    if !is_zenoh_port((*pinfo).srcport) && !is_zenoh_port((*pinfo).destport) {
+        return false;
+    }
+    // Later segments of a connection already carrying Zenoh may continue a batch.
+    (conversation::is_tracked(pinfo) || is_zenoh_heur(tvb, true))
+        && dissect_zenoh_tcp(tvb, pinfo, tree, data) != 0
 }
 
 unsafe extern "C" fn dissect_zenoh(
@@ -252,6 +466,18 @@ unsafe extern "C" fn dissect_zenoh(
     tree: *mut epan_sys::_proto_node,
     data: *mut std::ffi::c_void,
 ) -> std::ffi::c_int {
+    // Hand the connection over to the protocol carrying Zenoh, which calls us back with the
+    // payload of its own.
+    if let Some(handle) = carrier_handle(tvb, pinfo) {
+        if (*(*pinfo).fd).visited() == 0 {
+            epan_sys::conversation_set_dissector(
+                epan_sys::find_or_create_conversation(pinfo),
+                handle,
+            );
+        }
+        return epan_sys::call_dissector(handle, tvb, pinfo, tree);
+    }
+
     match (*pinfo).ptype {
         epan_sys::port_type_PT_TCP => dissect_zenoh_tcp(tvb, pinfo, tree, data),
         epan_sys::port_type_PT_UDP => dissect_zenoh_udp(tvb, pinfo, tree, data),
@@ -259,97 +485,289 @@ unsafe extern "C" fn dissect_zenoh(
     }
 }
 
+/// Returns the dissector of the protocol carrying Zenoh on this connection, if it starts a TLS,
+/// WebSocket or QUIC connection on a port of their range. As Zenoh locators of every kind use
+/// the same default port, such connections would otherwise be decoded as plain TCP or UDP.
+unsafe fn carrier_handle(
+    tvb: *mut epan_sys::tvbuff,
+    pinfo: *mut epan_sys::_packet_info,
+) -> Option<epan_sys::dissector_handle_t> {
+    // Links whose Zenoh handshake was captured are plain TCP or UDP.
+    if conversation::link_config(pinfo).is_some() {
+        return None;
+    }
+
+    let len = epan_sys::tvb_captured_length(tvb) as usize;
+    let bytes = slice::from_raw_parts(epan_sys::tvb_get_ptr(tvb, 0, len as _), len);
+    let (carrier, ports) = match (*pinfo).ptype {
+        // TLS handshake record holding a ClientHello.
+        epan_sys::port_type_PT_TCP
+            if len >= 6 && bytes[..2] == [0x16, 0x03] && bytes[2] <= 0x04 && bytes[5] == 0x01 =>
+        {
+            (
+                c"tls",
+                epan_sys::prefs_get_range_value(c"zenoh".as_ptr(), c"tls.port".as_ptr()),
+            )
+        }
+        // HTTP request upgrading the connection to WebSocket.
+        epan_sys::port_type_PT_TCP if bytes.starts_with(b"GET ") => (
+            c"http",
+            epan_sys::prefs_get_range_value(c"zenoh".as_ptr(), c"ws.port".as_ptr()),
+        ),
+        // QUIC long header packet of version 1, 2 or a draft.
+        epan_sys::port_type_PT_UDP if len >= 5 && bytes[0] & 0xc0 == 0xc0 => {
+            let version = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+            if version != 0x0000_0001 && version != 0x6b33_43cf && version >> 8 != 0xff_0000 {
+                return None;
+            }
+            (c"quic", QUIC_PORTS)
+        }
+        _ => return None,
+    };
+
+    let is_zenoh_port = |port| !ports.is_null() && epan_sys::value_is_in_range(ports, port);
+    if !is_zenoh_port((*pinfo).srcport) && !is_zenoh_port((*pinfo).destport) {
+        return None;
+    }
+    let handle = epan_sys::find_dissector(carrier.as_ptr());
+    (!handle.is_null()).then_some(handle)
+}
+
 /// Called by `tcp_dissect_pdus` to determine the full PDU length from the fixed-length header.
 ///
-/// Reads the 2-byte little-endian batch size and returns `BATCH_HEADER_LEN + batch_size`.
+/// Reads the 2-byte little-endian batch size and returns `BATCH_HEADER_LEN + batch_size`, or,
+/// if `data` points to `true` (low-latency transport), the 4-byte little-endian message size
+/// and returns `LOWLATENCY_HEADER_LEN + message_size`.
 unsafe extern "C" fn get_pdu_len_zenoh_tcp(
     _pinfo: *mut epan_sys::_packet_info,
     tvb: *mut epan_sys::tvbuff,
     offset: std::ffi::c_int,
-    _data: *mut std::ffi::c_void,
+    data: *mut std::ffi::c_void,
 ) -> std::ffi::c_uint {
-    let batch_size = epan_sys::tvb_get_letohs(tvb, offset) as std::ffi::c_uint;
-    (BATCH_HEADER_LEN as std::ffi::c_uint) + batch_size
+    if *(data as *const bool) {
+        let message_size = epan_sys::tvb_get_letohl(tvb, offset) as std::ffi::c_uint;
+        message_size.saturating_add(LOWLATENCY_HEADER_LEN as std::ffi::c_uint)
+    } else {
+        let batch_size = epan_sys::tvb_get_letohs(tvb, offset) as std::ffi::c_uint;
+        (BATCH_HEADER_LEN as std::ffi::c_uint) + batch_size
+    }
 }
 
+/// Key of the per-frame [`ZenohFrameData`]. Key 1 is used by `conversation`.
 const PROTO_DATA_KEY_FRAME: u32 = 0;
 
-/// Per-frame state shared between `dissect_zenoh_tcp` calls for the same frame.
+/// Per-frame state shared between the batches of a frame, whatever the link.
 /// Stored via `p_add_proto_data` so it survives when the TCP layer calls us
 /// multiple times (e.g. reassembled PDU + remaining segment data).
-#[repr(C)]
 struct ZenohFrameData {
-    /// The protocol item ("Zenoh Protocol, …") — used to append ZID text.
+    /// The TVB and tree the dissector was first called with for this frame.
+    tvb: *mut epan_sys::tvbuff,
+    tree: *mut epan_sys::_proto_node,
+    /// The protocol item ("Zenoh Protocol, …") — summarises the batches and ZIDs. Null until a
+    /// batch of the frame is decoded, see [`Self::proto_tree`].
     proto_ti: *mut epan_sys::_proto_node,
-    /// The protocol subtree — ZID fields are added here.
+    /// The protocol subtree — batches and ZID fields are added here.
     proto_tree: *mut epan_sys::proto_tree,
     /// Whether ZID fields have already been added to the tree.
     zids_added: bool,
+    /// ZIDs of the session, appended to the protocol item.
+    zid_text: String,
+    /// Info column summary of the messages of all the batches of the frame.
+    info: SizedSummary,
+    /// Number of batches of the frame dissected so far.
+    batch_count: u32,
+    /// Number of messages of these batches.
+    message_count: usize,
+}
+
+impl ZenohFrameData {
+    /// Returns the data of this frame, created on the first call.
+    unsafe fn get_or_new(
+        tvb: *mut epan_sys::tvbuff,
+        pinfo: *mut epan_sys::_packet_info,
+        tree: *mut epan_sys::_proto_node,
+    ) -> *mut Self {
+        let proto_id = PROTOCOL_DATA.with_borrow(|d| d.id);
+        let scope = (*pinfo).pool;
+
+        let existing = epan_sys::p_get_proto_data(scope, pinfo, proto_id, PROTO_DATA_KEY_FRAME);
+        if !existing.is_null() {
+            return existing as *mut Self;
+        }
+
+        let fd = Self::new_in(scope, tvb, tree);
+        epan_sys::p_add_proto_data(scope, pinfo, proto_id, PROTO_DATA_KEY_FRAME, fd as *mut _);
+        fd
+    }
+
+    /// Allocates the frame data in the packet scope, dropped along with it.
+    unsafe fn new_in(
+        scope: *mut epan_sys::wmem_allocator_t,
+        tvb: *mut epan_sys::tvbuff,
+        tree: *mut epan_sys::_proto_node,
+    ) -> *mut Self {
+        unsafe extern "C" fn drop_frame_data(
+            _allocator: *mut epan_sys::wmem_allocator_t,
+            _event: epan_sys::wmem_cb_event_t,
+            user_data: *mut std::ffi::c_void,
+        ) -> bool {
+            std::ptr::drop_in_place(user_data as *mut ZenohFrameData);
+            // Unregister the callback, the scope is done with.
+            false
+        }
+
+        let fd = epan_sys::wmem_alloc(scope, std::mem::size_of::<Self>()) as *mut Self;
+        fd.write(Self {
+            tvb,
+            tree,
+            proto_ti: std::ptr::null_mut(),
+            proto_tree: std::ptr::null_mut(),
+            zids_added: false,
+            zid_text: String::new(),
+            info: info_summary(),
+            batch_count: 0,
+            message_count: 0,
+        });
+        epan_sys::wmem_register_callback(scope, Some(drop_frame_data), fd as _);
+        fd
+    }
+
+    /// Returns the protocol subtree of the frame, adding the protocol item to the frame tree and
+    /// claiming the Protocol column on the first call. Only called once something decoded, so
+    /// that segments which aren't Zenoh are left to other dissectors untouched.
+    unsafe fn proto_tree(
+        &mut self,
+        pinfo: *mut epan_sys::_packet_info,
+    ) -> *mut epan_sys::proto_tree {
+        if self.proto_ti.is_null() {
+            epan_sys::col_set_str(
+                (*pinfo).cinfo,
+                epan_sys::COL_PROTOCOL as _,
+                c"Zenoh".as_ptr(),
+            );
+            PROTOCOL_DATA.with_borrow(|data| {
+                self.proto_ti = epan_sys::proto_tree_add_item(
+                    self.tree,
+                    data.id,
+                    self.tvb,
+                    0,
+                    -1,
+                    epan_sys::ENC_NA,
+                );
+                let st = *data
+                    .st_map
+                    .get("zenoh")
+                    .expect("zenoh subtree not registered");
+                self.proto_tree = epan_sys::proto_item_add_subtree(self.proto_ti, st);
+            });
+        }
+        self.proto_tree
+    }
+
+    /// Add the ZID fields once per frame, then sum up the batches dissected so far in the
+    /// protocol item and the Info column.
+    unsafe fn update(&mut self, tvb: *mut epan_sys::tvbuff, pinfo: *mut epan_sys::_packet_info) {
+        if !self.zids_added {
+            self.zids_added = true;
+            self.zid_text = conversation::update_tree(tvb, pinfo, self.proto_tree(pinfo));
+        }
+
+        let text = CString::new(format!(
+            "Zenoh Protocol, Batches: {}, Messages: {}{}",
+            self.batch_count, self.message_count, self.zid_text
+        ))
+        .unwrap();
+        epan_sys::proto_item_set_text(self.proto_ti, c"%s".as_ptr(), text.as_ptr());
+
+        let info = if self.batch_count > 1 {
+            format!("{} ({} batches)", self.info, self.batch_count)
+        } else {
+            self.info.to_string()
+        };
+        let info = CString::new(info.replace('\0', "")).unwrap();
+        epan_sys::col_clear((*pinfo).cinfo, epan_sys::COL_INFO as _);
+        epan_sys::col_add_str((*pinfo).cinfo, epan_sys::COL_INFO as _, info.as_ptr());
+    }
 }
 
-/// Top-level TCP dissector: creates a single "Zenoh Protocol" protocol tree (once per
-/// frame), delegates to `tcp_dissect_pdus` for reassembly and PDU boundary detection
-/// (which calls `dissect_zenoh_pdu` for each complete batch), then adds ZID fields.
+/// Top-level TCP dissector: delegates to `tcp_dissect_pdus` for reassembly and PDU boundary
+/// detection (which calls `dissect_pdu_zenoh_tcp` for each complete batch), then sums up the
+/// batches in a single "Zenoh Protocol" protocol tree (once per frame) and adds ZID fields.
+/// Returns 0 if the segment neither completed nor started a batch of a Zenoh conversation.
+///
+/// Nothing here is TCP-specific, so the same function backs the `zenoh_stream` dissector
+/// used for unixsock-stream and vsock links. Reassembly then happens only if the lower
+/// layer supports desegmentation.
 unsafe extern "C" fn dissect_zenoh_tcp(
     tvb: *mut epan_sys::tvbuff,
     pinfo: *mut epan_sys::_packet_info,
     tree: *mut epan_sys::_proto_node,
     _data: *mut std::ffi::c_void,
 ) -> std::ffi::c_int {
-    static C_STR_ZENOH: LazyLock<CString> = LazyLock::new(|| CString::new("Zenoh").unwrap());
-    epan_sys::col_set_str(
-        (*pinfo).cinfo,
-        epan_sys::COL_PROTOCOL as _,
-        C_STR_ZENOH.as_ptr(),
-    );
-
-    let proto_id = PROTOCOL_DATA.with_borrow(|d| d.id);
-    let scope = (*pinfo).pool;
-
-    // Create the protocol tree only once per frame. The TCP layer may call us
-    // multiple times for the same frame (reassembled data + remaining segment).
-    let existing = epan_sys::p_get_proto_data(scope, pinfo, proto_id, PROTO_DATA_KEY_FRAME);
-    let frame_data: *mut ZenohFrameData = if existing.is_null() {
-        let (ti, subtree) = PROTOCOL_DATA.with(|data| {
-            let borrowed = data.borrow();
-            let ti = epan_sys::proto_tree_add_item(tree, borrowed.id, tvb, 0, -1, epan_sys::ENC_NA);
-            let st = *borrowed
-                .st_map
-                .get("zenoh")
-                .expect("zenoh subtree not registered");
-            let subtree = epan_sys::proto_item_add_subtree(ti, st);
-            (ti, subtree)
-        });
+    // The TCP layer may call us multiple times for the same frame (reassembled data +
+    // remaining segment), all of them adding to the same protocol tree.
+    let frame_data = ZenohFrameData::get_or_new(tvb, pinfo, tree);
+    let batch_count = (*frame_data).batch_count;
+
+    // After the handshake of a low-latency session, PDUs are single messages with a larger
+    // size header. The mode is passed to the PDU callbacks through their `data` argument.
+    let mut lowlatency = is_lowlatency(pinfo);
+    let header_len = if lowlatency {
+        LOWLATENCY_HEADER_LEN
+    } else {
+        BATCH_HEADER_LEN
+    };
 
-        let fd = epan_sys::wmem_alloc0(scope, std::mem::size_of::<ZenohFrameData>())
-            as *mut ZenohFrameData;
-        (*fd).proto_ti = ti;
-        (*fd).proto_tree = subtree;
-        epan_sys::p_add_proto_data(scope, pinfo, proto_id, PROTO_DATA_KEY_FRAME, fd as *mut _);
-        fd
+    // A stream captured in the middle of a session may start anywhere within a batch.
+    let skipped = if lowlatency {
+        0
     } else {
-        existing as *mut ZenohFrameData
+        resync_offset(tvb, pinfo)
     };
+    let len = epan_sys::tvb_captured_length(tvb) as usize;
+    // A segment without any batch boundary is only Zenoh on a conversation known to be.
+    if skipped == len && !conversation::is_tracked(pinfo) {
+        return 0;
+    }
+    if skipped != 0 {
+        add_skipped(tvb, pinfo, &mut *frame_data, skipped);
+    }
 
-    // `tcp_dissect_pdus` calls dissect_zenoh_pdu for each complete batch.
-    // Batch subtrees are added as siblings of the protocol tree on the frame tree.
-    epan_sys::tcp_dissect_pdus(
-        tvb,
-        pinfo,
-        tree,
-        true,
-        BATCH_HEADER_LEN as std::ffi::c_uint,
-        Some(get_pdu_len_zenoh_tcp),
-        Some(dissect_pdu_zenoh_tcp),
-        std::ptr::null_mut(),
-    );
+    if skipped < len {
+        let pdus = if skipped == 0 {
+            tvb
+        } else {
+            epan_sys::tvb_new_subset_remaining(tvb, skipped as _)
+        };
+        // `tcp_dissect_pdus` calls dissect_pdu_zenoh_tcp for each complete batch.
+        epan_sys::tcp_dissect_pdus(
+            pdus,
+            pinfo,
+            tree,
+            true,
+            header_len as std::ffi::c_uint,
+            Some(get_pdu_len_zenoh_tcp),
+            Some(dissect_pdu_zenoh_tcp),
+            &raw mut lowlatency as *mut _,
+        );
+        // The lower layer expects the data to reassemble relative to what it handed us.
+        if (*pinfo).desegment_len != 0 {
+            (*pinfo).desegment_offset += skipped as std::ffi::c_int;
+        }
+    }
 
-    // Add ZID fields to the protocol subtree and update the protocol item text.
-    // Done after tcp_dissect_pdus so that InitSyn/InitAck in any batch have had
-    // a chance to update the conversation state. Only once per frame.
-    if !(*frame_data).zids_added {
-        (*frame_data).zids_added = true;
-        conversation::update_tree(tvb, pinfo, (*frame_data).proto_tree, (*frame_data).proto_ti);
+    // Segments which neither complete nor start a batch are only claimed on conversations
+    // already known to carry Zenoh, or to show the bytes skipped while resynchronising.
+    let decoded = (*frame_data).batch_count != batch_count;
+    if !decoded && skipped == 0 && (*pinfo).desegment_len == 0 && !conversation::is_tracked(pinfo) {
+        return 0;
+    }
+
+    // Done after tcp_dissect_pdus so that InitSyn/InitAck in any batch have had a chance to
+    // update the conversation state. Segments which only continue a batch keep the Info column
+    // set by TCP.
+    if decoded {
+        (*frame_data).update(tvb, pinfo);
     }
 
     epan_sys::tvb_reported_length(tvb) as std::ffi::c_int
@@ -357,229 +775,520 @@ unsafe extern "C" fn dissect_zenoh_tcp(
 
 /// Dissect a single, complete Zenoh batch PDU.
 ///
-/// The TVB contains exactly `BATCH_HEADER_LEN + batch_payload` bytes. `tcp_dissect_pdus`
-/// has already handled reassembly and framing.
-///
-/// Batch subtrees are added to the frame `tree` as siblings of the "Zenoh Protocol" item.
+/// The TVB contains exactly `BATCH_HEADER_LEN + batch_payload` bytes, or
+/// `LOWLATENCY_HEADER_LEN + message` bytes if `data` points to `true` (low-latency transport).
+/// `tcp_dissect_pdus` has already handled reassembly and framing.
 unsafe extern "C" fn dissect_pdu_zenoh_tcp(
     tvb: *mut epan_sys::tvbuff,
     pinfo: *mut epan_sys::_packet_info,
-    tree: *mut epan_sys::_proto_node,
-    _data: *mut std::ffi::c_void,
+    _tree: *mut epan_sys::_proto_node,
+    data: *mut std::ffi::c_void,
 ) -> std::ffi::c_int {
+    let lowlatency = *(data as *const bool);
+    let header_len = if lowlatency {
+        LOWLATENCY_HEADER_LEN
+    } else {
+        BATCH_HEADER_LEN
+    };
+
     let tvb_len = epan_sys::tvb_reported_length(tvb) as usize;
-    if tvb_len < BATCH_HEADER_LEN {
+    if tvb_len < header_len {
+        return 0;
+    }
+    // Links without desegmentation may hand us a truncated batch.
+    if (epan_sys::tvb_captured_length(tvb) as usize) < tvb_len {
+        ws_log::message!("zenoh_tcp: truncated batch (no={})", (*pinfo).num);
         return 0;
     }
 
-    // Skip the 2-byte length header; the rest is the batch payload.
-    let payload_len = tvb_len - BATCH_HEADER_LEN;
-    let payload_ptr = epan_sys::tvb_get_ptr(tvb, BATCH_HEADER_LEN as _, payload_len as _);
-    let payload_slice = slice::from_raw_parts(payload_ptr, payload_len);
+    // The batches of the frame are added to its protocol tree, see `dissect_zenoh_tcp`.
+    let proto_id = PROTOCOL_DATA.with_borrow(|d| d.id);
+    let frame_data =
+        &mut *(epan_sys::p_get_proto_data((*pinfo).pool, pinfo, proto_id, PROTO_DATA_KEY_FRAME)
+            as *mut ZenohFrameData);
 
-    let msgs = {
-        let mut rbatch = match new_rbatch(payload_slice, IS_COMPRESSION) {
-            Ok(rbatch) => rbatch,
-            Err(err) => {
-                ws_log::message!("zenoh_tcp: {err} (no={})", (*pinfo).num);
-                return 0;
-            }
-        };
-        let mut msgs = Vec::new();
+    let len = dissect_batch(tvb, pinfo, frame_data, header_len, lowlatency, "zenoh_tcp");
+    if len != 0 {
+        conversation::mark_synced(pinfo);
+    }
+    len
+}
 
-        let mut offset: usize = 0;
-        while !rbatch.is_empty() {
-            let Ok((msg, len)): Result<(TransportMessage, BatchSize), _> = rbatch.decode() else {
-                ws_log::message!(
-                    "zenoh_tcp: failed to decode transport message (no={})",
-                    (*pinfo).num
-                );
-                return 0;
-            };
+/// Returns the number of bytes preceding the first batch boundary of a segment, 0 unless its
+/// sender's stream was captured in the middle of a session and no batch of it was decoded yet,
+/// see [`heuristics::find_batch_boundary`]. Segments without a boundary are skipped entirely.
+unsafe fn resync_offset(tvb: *mut epan_sys::tvbuff, pinfo: *mut epan_sys::_packet_info) -> usize {
+    let len = epan_sys::tvb_captured_length(tvb) as usize;
+    if (*(*pinfo).fd).visited() != 0 {
+        return conversation::recorded_resync(pinfo, len);
+    }
+    if len == 0 || conversation::is_synced(pinfo) {
+        return 0;
+    }
 
-            msgs.push(Message {
-                msg,
-                len: len as _,
-                offset,
-            });
-            offset += len as usize;
-        }
+    let data = slice::from_raw_parts(epan_sys::tvb_get_ptr(tvb, 0, len as _), len);
+    let compression =
+        conversation::link_config(pinfo).map_or(IS_COMPRESSION, |config| config.compression);
+    let skipped = heuristics::find_batch_boundary(data, compression).unwrap_or(len);
+    if skipped != 0 {
+        conversation::record_resync(pinfo, len, skipped);
+    }
+    skipped
+}
 
-        msgs
+/// Add the bytes skipped by [`resync_offset`] to the protocol tree of the frame.
+unsafe fn add_skipped(
+    tvb: *mut epan_sys::tvbuff,
+    pinfo: *mut epan_sys::_packet_info,
+    frame_data: &mut ZenohFrameData,
+    skipped: usize,
+) {
+    let proto_tree = frame_data.proto_tree(pinfo);
+    let hf = PROTOCOL_DATA.with_borrow(|d| d.hf_map["zenoh.skipped"]);
+    epan_sys::proto_tree_add_uint64(proto_tree, hf, tvb, 0, skipped as _, skipped as _);
+    let text = if skipped < epan_sys::tvb_captured_length(tvb) as usize {
+        format!("Stream joined mid-session, resynchronised after {skipped} bytes")
+    } else {
+        format!("Stream joined mid-session, no batch boundary in these {skipped} bytes")
     };
+    expert::add_expert(
+        proto_tree,
+        pinfo,
+        &raw mut expert::EI_RESYNC,
+        tvb,
+        0,
+        skipped,
+        &text,
+    );
+}
 
-    let summary = PROTOCOL_DATA.with(|data| {
-        let borrowed_data = data.borrow();
+/// Dissect a Zenoh UDP datagram or WebSocket message (entire payload is a single batch, no
+/// length prefix), or a scouting message sent to or from the scouting port.
+unsafe extern "C" fn dissect_zenoh_udp(
+    tvb: *mut epan_sys::tvbuff,
+    pinfo: *mut epan_sys::_packet_info,
+    tree: *mut epan_sys::_proto_node,
+    _data: *mut std::ffi::c_void,
+) -> std::ffi::c_int {
+    if epan_sys::tvb_reported_length(tvb) == 0 {
+        return 0;
+    }
 
-        // Add a batch subtree on the frame tree (sibling of "Zenoh Protocol").
-        let batch_tree = TreeArgs {
-            tree,
+    if (*pinfo).ptype == epan_sys::port_type_PT_UDP
+        && ((*pinfo).srcport == SCOUTING_PORT || (*pinfo).destport == SCOUTING_PORT)
+    {
+        return dissect_scouting(tvb, pinfo, tree);
+    }
+
+    // A low-latency datagram holds a single message without any header.
+    let frame_data = ZenohFrameData::get_or_new(tvb, pinfo, tree);
+    let len = dissect_batch(
+        tvb,
+        pinfo,
+        &mut *frame_data,
+        0,
+        is_lowlatency(pinfo),
+        "zenoh_udp",
+    );
+    if len != 0 {
+        (*frame_data).update(tvb, pinfo);
+    }
+    len
+}
+
+/// Dissect a Scout or Hello message. Scouting isn't part of any session, so the message is
+/// added right below the protocol item.
+unsafe fn dissect_scouting(
+    tvb: *mut epan_sys::tvbuff,
+    pinfo: *mut epan_sys::_packet_info,
+    tree: *mut epan_sys::_proto_node,
+) -> std::ffi::c_int {
+    let tvb_len = epan_sys::tvb_reported_length(tvb) as usize;
+    if (epan_sys::tvb_captured_length(tvb) as usize) < tvb_len {
+        return 0;
+    }
+    let bytes = slice::from_raw_parts(epan_sys::tvb_get_ptr(tvb, 0, tvb_len as _), tvb_len);
+    let Ok(msg): Result<ScoutingMessage, _> = Zenoh080::new().read(&mut bytes.reader()) else {
+        ws_log::message!(
+            "zenoh_udp: failed to decode scouting message (no={})",
+            (*pinfo).num
+        );
+        return 0;
+    };
+
+    epan_sys::col_set_str(
+        (*pinfo).cinfo,
+        epan_sys::COL_PROTOCOL as _,
+        c"Zenoh".as_ptr(),
+    );
+    PROTOCOL_DATA.with(|data| {
+        let borrowed_data = data.borrow();
+        let ti =
+            epan_sys::proto_tree_add_item(tree, borrowed_data.id, tvb, 0, -1, epan_sys::ENC_NA);
+        let st = *borrowed_data
+            .st_map
+            .get("zenoh")
+            .expect("zenoh subtree not registered");
+        let args = TreeArgs {
+            tree: epan_sys::proto_item_add_subtree(ti, st),
             tvb,
             hf_map: &borrowed_data.hf_map,
             st_map: &borrowed_data.st_map,
             start: 0,
             length: tvb_len,
-        }
-        .make_subtree("zenoh.batch", &format!("Batch, Len: {payload_len}"))
-        .unwrap();
-
-        // Update conversation state (ZIDs) from this batch's messages.
-        for m in &msgs {
-            conversation::update_state(pinfo, &m.msg);
-        }
-
-        for m in &msgs {
-            // Message offsets are relative to the batch payload; shift by BATCH_HEADER_LEN
-            // to make them relative to the TVB.
-            let msg_tree = TreeArgs {
-                start: BATCH_HEADER_LEN + m.offset,
-                length: m.len,
-                ..batch_tree
-            };
-            m.msg.add_to_tree("zenoh", &msg_tree).unwrap();
-        }
-
-        let mut batch_summary = SizedSummary::new(MAX_BATCH_SUMMARY);
-        for m in &msgs {
-            batch_summary.append(|| {
-                let mut s = transport_message_summary(&m.msg);
-                if s.len() > MSG_SUMMARY_LIMIT {
-                    s.truncate(MSG_SUMMARY_LIMIT);
-                    s += "...]";
-                }
-                s
-            });
-        }
-        batch_summary
+        };
+        msg.add_to_tree("zenoh", &args).unwrap();
     });
 
-    let summary_c_str = CString::new(format!("{summary}")).unwrap();
+    let info = match &msg.body {
+        ScoutingBody::Scout(scout) => format!("Scout {}", scout.what),
+        ScoutingBody::Hello(hello) => format!("Hello {} {}", hello.whatami, hello.zid),
+    };
+    let info = CString::new(info).unwrap();
     epan_sys::col_clear((*pinfo).cinfo, epan_sys::COL_INFO as _);
-    epan_sys::col_add_str(
-        (*pinfo).cinfo,
-        epan_sys::COL_INFO as _,
-        summary_c_str.as_ptr(),
-    );
+    epan_sys::col_add_str((*pinfo).cinfo, epan_sys::COL_INFO as _, info.as_ptr());
 
     tvb_len as std::ffi::c_int
 }
 
-/// Dissect a Zenoh UDP datagram (entire payload is a single batch, no length prefix).
-unsafe extern "C" fn dissect_zenoh_udp(
+/// Dissect a batch, or a low-latency message, as a `zenoh.batch` subtree of the protocol tree
+/// of its frame, so that every link has the same layout. `header_len` is the length of the
+/// size header of stream links, 0 for datagrams. Returns the length of the batch, or 0 if it
+/// can't be decoded.
+unsafe fn dissect_batch(
     tvb: *mut epan_sys::tvbuff,
     pinfo: *mut epan_sys::_packet_info,
-    tree: *mut epan_sys::_proto_node,
-    _data: *mut std::ffi::c_void,
+    frame_data: &mut ZenohFrameData,
+    header_len: usize,
+    lowlatency: bool,
+    link: &str,
 ) -> std::ffi::c_int {
-    epan_sys::col_add_str(
-        (*pinfo).cinfo,
-        epan_sys::COL_PROTOCOL as _,
-        c"Zenoh".as_ptr(),
-    );
-
     let tvb_len = epan_sys::tvb_reported_length(tvb) as usize;
-    if tvb_len == 0 {
-        return 0;
-    }
 
-    let tvb_ptr = epan_sys::tvb_get_ptr(tvb, 0, tvb_len as _);
-    let tvb_slice = slice::from_raw_parts(tvb_ptr, tvb_len);
+    // Skip the length header; the rest is the batch payload.
+    let payload_len = tvb_len - header_len;
+    let payload_ptr = epan_sys::tvb_get_ptr(tvb, header_len as _, payload_len as _);
+    let payload_slice = slice::from_raw_parts(payload_ptr, payload_len);
 
-    let msgs = {
-        let mut rbatch = match new_rbatch(tvb_slice, IS_COMPRESSION) {
-            Ok(rbatch) => rbatch,
+    // Batches of sessions with compression start with a header flagging compressed batches.
+    let mut has_batch_header = false;
+    let msgs = if lowlatency {
+        match decode_lowlatency(payload_slice) {
+            Ok(msg) => vec![Message {
+                body: MessageBody::LowLatency(msg),
+                len: payload_len,
+                offset: 0,
+                network_lens: Vec::new(),
+            }],
             Err(err) => {
-                ws_log::message!("zenoh_udp: {err} (no={})", (*pinfo).num);
+                ws_log::message!("{link}: {err} (no={})", (*pinfo).num);
+                return 0;
+            }
+        }
+    } else {
+        let mut rbatch = match session_rbatch(pinfo, payload_slice) {
+            Ok((rbatch, compression)) => {
+                has_batch_header = compression;
+                rbatch
+            }
+            Err(err) => {
+                ws_log::message!("{link}: {err} (no={})", (*pinfo).num);
                 return 0;
             }
         };
         let mut msgs = Vec::new();
 
-        let mut offset: usize = 0;
-        while !rbatch.is_empty() {
-            let Ok((msg, len)): Result<(TransportMessage, BatchSize), _> = rbatch.decode() else {
-                ws_log::message!(
-                    "zenoh_udp: failed to decode transport message (no={})",
-                    (*pinfo).num
-                );
-                return 0;
-            };
-
-            msgs.push(Message {
-                msg,
-                len: len as _,
-                offset,
-            });
-            offset += len as usize;
+        let mut offset: usize = if has_batch_header { 1 } else { 0 };
+        let compressed = has_batch_header
+            && payload_slice
+                .first()
+                .is_some_and(|header| header & BATCH_HEADER_COMPRESSION != 0);
+        if compressed {
+            // The messages of a compressed batch only exist once inflated by the batch.
+            while !rbatch.is_empty() {
+                let Ok((msg, len)): Result<(TransportMessage, BatchSize), _> = rbatch.decode()
+                else {
+                    ws_log::message!(
+                        "{link}: failed to decode transport message (no={})",
+                        (*pinfo).num
+                    );
+                    return 0;
+                };
+
+                msgs.push(Message {
+                    body: MessageBody::Transport(msg),
+                    len: len as _,
+                    offset,
+                    network_lens: Vec::new(),
+                });
+                offset += len as usize;
+            }
+        } else {
+            let mut reader = &payload_slice[offset..];
+            while !reader.is_empty() {
+                let remaining = reader.len();
+                let Some((msg, network_lens)) = decode_transport(&mut reader) else {
+                    ws_log::message!(
+                        "{link}: failed to decode transport message (no={})",
+                        (*pinfo).num
+                    );
+                    return 0;
+                };
+
+                let len = remaining - reader.len();
+                msgs.push(Message {
+                    body: MessageBody::Transport(msg),
+                    len,
+                    offset,
+                    network_lens,
+                });
+                offset += len;
+            }
         }
 
         msgs
     };
 
-    let summary = PROTOCOL_DATA.with(|data| {
+    PROTOCOL_DATA.with(|data| {
         let borrowed_data = data.borrow();
 
-        let ti =
-            epan_sys::proto_tree_add_item(tree, borrowed_data.id, tvb, 0, -1, epan_sys::ENC_NA);
-        let st = *borrowed_data
-            .st_map
-            .get("zenoh")
-            .expect("zenoh subtree not registered");
-        let zenoh_tree = epan_sys::proto_item_add_subtree(ti, st);
-
-        let tree_args = TreeArgs {
-            tree: zenoh_tree,
+        let batch_name = if lowlatency {
+            format!("Low-latency message, Len: {payload_len}")
+        } else {
+            format!("Batch, Len: {payload_len}, Messages: {}", msgs.len())
+        };
+        let batch_tree = TreeArgs {
+            tree: frame_data.proto_tree(pinfo),
             tvb,
             hf_map: &borrowed_data.hf_map,
             st_map: &borrowed_data.st_map,
             start: 0,
             length: tvb_len,
-        };
+        }
+        .make_subtree("zenoh.batch", &batch_name)
+        .unwrap();
 
+        if header_len != 0 {
+            epan_sys::proto_tree_add_uint64(
+                batch_tree.tree,
+                batch_tree.get_hf("zenoh.batch.length").unwrap(),
+                tvb,
+                0,
+                header_len as _,
+                payload_len as _,
+            );
+        }
+        if has_batch_header {
+            let header = payload_slice.first().copied().unwrap_or_default();
+            epan_sys::proto_tree_add_uint64(
+                batch_tree.tree,
+                batch_tree.get_hf("zenoh.batch.header").unwrap(),
+                tvb,
+                header_len as _,
+                1,
+                header as _,
+            );
+            wireshark::set_generated(epan_sys::proto_tree_add_boolean(
+                batch_tree.tree,
+                batch_tree.get_hf("zenoh.batch.compressed").unwrap(),
+                tvb,
+                header_len as _,
+                1,
+                (header & BATCH_HEADER_COMPRESSION) as _,
+            ));
+        }
+
+        // Update conversation state (ZIDs) from this batch's messages.
         for m in &msgs {
-            conversation::update_state(pinfo, &m.msg);
+            conversation::update_state(pinfo, &m.body);
         }
-        conversation::update_tree(tvb, pinfo, zenoh_tree, ti);
 
         for m in &msgs {
+            // Message offsets are relative to the batch payload; shift by the header length
+            // to make them relative to the TVB.
             let msg_tree = TreeArgs {
-                start: m.offset,
+                start: header_len + m.offset,
                 length: m.len,
-                ..tree_args
+                ..batch_tree
             };
-            m.msg.add_to_tree("zenoh", &msg_tree).unwrap();
+            let reassembled = dissect_message(pinfo, &msg_tree, m);
+            append_summary(pinfo, &mut frame_data.info, m, reassembled.as_ref());
         }
+    });
 
-        let mut batch_summary = SizedSummary::new(MAX_BATCH_SUMMARY);
-        for m in &msgs {
-            batch_summary.append(|| {
-                let mut s = transport_message_summary(&m.msg);
-                if s.len() > MSG_SUMMARY_LIMIT {
-                    s.truncate(MSG_SUMMARY_LIMIT);
-                    s += "...]";
-                }
-                s
-            });
-        }
-        batch_summary
+    frame_data.batch_count += 1;
+    frame_data.message_count += msgs.len();
+
+    tvb_len as std::ffi::c_int
+}
+
+/// Add a decoded message to the tree, along with the network message completed by a Fragment
+/// and the link states of network OAMs, and feed it to the expert info, the taps and the follow
+/// stream. Returns the network message completed by a Fragment.
+unsafe fn dissect_message(
+    pinfo: *mut epan_sys::_packet_info,
+    args: &TreeArgs,
+    m: &Message,
+) -> Option<NetworkMessage> {
+    m.body.add_to_tree("zenoh", args).unwrap();
+    conversation::check_message(pinfo, args, &m.body);
+    conversation::add_keepalive_timing(pinfo, args, &m.body);
+    let (reassembled, reassembled_len) = add_reassembled(pinfo, args, &m.body).unzip();
+    for msg in conversation::network_messages(&m.body)
+        .iter()
+        .chain(reassembled.as_ref())
+    {
+        linkstate::add_link_states(pinfo, args, msg).unwrap();
+    }
+    tap::queue_message(
+        pinfo,
+        &m.body,
+        m.len,
+        &m.network_lens,
+        reassembled.as_ref(),
+        reassembled_len.unwrap_or_default(),
+    );
+    topology::queue_events(pinfo, &m.body, reassembled.as_ref());
+    follow::queue_message(pinfo, args.tvb, &m.body, reassembled.as_ref());
+    export_objects::queue_objects(pinfo, &m.body, reassembled.as_ref());
+    reassembled
+}
+
+/// Creates the Info column summary of a packet, limited by the preferences.
+unsafe fn info_summary() -> SizedSummary {
+    SizedSummary::new(INFO_MAX_MESSAGES as _, INFO_MAX_LENGTH as _)
+}
+
+/// Append the summaries of a message, with the verbosity set by the preferences.
+unsafe fn append_summary(
+    pinfo: *mut epan_sys::_packet_info,
+    info: &mut SizedSummary,
+    m: &Message,
+    reassembled: Option<&NetworkMessage>,
+) {
+    info.append(|| {
+        summary::message_summaries(
+            pinfo,
+            &m.body,
+            reassembled,
+            summary::Verbosity::from_pref(INFO_VERBOSITY),
+        )
     });
+}
 
-    let summary_c_str = CString::new(format!("{summary}")).unwrap();
-    epan_sys::col_clear((*pinfo).cinfo, epan_sys::COL_INFO as _);
-    epan_sys::col_add_str(
-        (*pinfo).cinfo,
-        epan_sys::COL_INFO as _,
-        summary_c_str.as_ptr(),
+/// Add the network message completed by the last Fragment of a message, as a data source of
+/// its own, and return it along with its length.
+unsafe fn add_reassembled(
+    pinfo: *mut epan_sys::_packet_info,
+    args: &TreeArgs,
+    body: &MessageBody,
+) -> Option<(NetworkMessage, usize)> {
+    let MessageBody::Transport(TransportMessage {
+        body: TransportBody::Fragment(fragment),
+    }) = body
+    else {
+        return None;
+    };
+    let reassembled = conversation::find_reassembled(pinfo, fragment).as_ref()?;
+
+    let tvb = epan_sys::tvb_new_child_real_data(
+        args.tvb,
+        reassembled.data,
+        reassembled.len,
+        reassembled.len as _,
     );
+    epan_sys::add_new_data_source(pinfo, tvb, c"Reassembled Zenoh".as_ptr());
 
-    tvb_len as std::ffi::c_int
+    let Some(msg) = reassembled.decode(fragment.reliability) else {
+        ws_log::message!(
+            "zenoh: failed to decode reassembled network message (no={})",
+            (*pinfo).num
+        );
+        return None;
+    };
+
+    let tree = TreeArgs {
+        tvb,
+        start: 0,
+        length: reassembled.len as _,
+        ..*args
+    }
+    .make_subtree(
+        "zenoh.reassembled",
+        &format!(
+            "Reassembled Network Message, Len: {}, Fragments: {}",
+            reassembled.len, reassembled.count
+        ),
+    )
+    .unwrap();
+    wireshark::set_generated(epan_sys::proto_tree_add_uint(
+        tree.tree,
+        tree.get_hf("zenoh.reassembled.first_frame").unwrap(),
+        tvb,
+        0,
+        0,
+        reassembled.first_frame,
+    ));
+    wireshark::set_generated(epan_sys::proto_tree_add_uint64(
+        tree.tree,
+        tree.get_hf("zenoh.reassembled.fragment_count").unwrap(),
+        tvb,
+        0,
+        0,
+        reassembled.count as _,
+    ));
+    msg.add_to_tree("zenoh.reassembled", &tree).unwrap();
+    Some((msg, reassembled.len as _))
+}
+
+/// Creates the reader of a batch with the compression negotiated by its session, falling
+/// back to the global preference when the session's handshake wasn't captured. Also returns
+/// whether the batch was read with compression enabled, i.e. with a batch header.
+unsafe fn session_rbatch(
+    pinfo: *mut epan_sys::_packet_info,
+    batch: &[u8],
+) -> Result<(RBatch<ZSlice>, bool), Box<dyn std::error::Error>> {
+    match conversation::link_config(pinfo) {
+        Some(config) => {
+            new_rbatch(batch, config.compression).map(|rbatch| (rbatch, config.compression))
+        }
+        None => new_rbatch_with_fallback(batch, IS_COMPRESSION),
+    }
+}
+
+/// Returns whether this packet belongs to the low-latency phase of a session, falling back to
+/// the global preference when the session's handshake wasn't captured.
+unsafe fn is_lowlatency(pinfo: *mut epan_sys::_packet_info) -> bool {
+    match conversation::link_config(pinfo) {
+        Some(config) => config.lowlatency,
+        None => FORCE_LOWLATENCY,
+    }
 }
 
-/// A single decoded transport message with its position within the batch payload.
-#[derive(Debug, PartialEq)]
+/// A single decoded message with its position within the batch payload.
+#[derive(Debug)]
 struct Message {
-    pub msg: TransportMessage,
-    /// Byte offset relative to the start of the batch payload (after the 2-byte header).
+    pub body: MessageBody,
+    /// Byte offset relative to the start of the batch payload (after the length header).
     pub offset: usize,
     pub len: usize,
+    /// Lengths of the network messages of a Frame, which aren't delimited in the batch. Empty
+    /// for other messages and within compressed batches.
+    pub network_lens: Vec<usize>,
+}
+
+/// A transport message of a batch, or a message of the low-latency transport.
+#[derive(Debug)]
+enum MessageBody {
+    Transport(TransportMessage),
+    LowLatency(TransportMessageLowLatency),
+}
+
+impl MessageBody {
+    fn add_to_tree(&self, prefix: &str, args: &TreeArgs) -> Result<()> {
+        match self {
+            Self::Transport(msg) => msg.add_to_tree(prefix, args),
+            Self::LowLatency(msg) => msg.add_to_tree(prefix, args),
+        }
+    }
 }
@@ -0,0 +1,270 @@
+use std::ffi::CString;
+
+use anyhow::Result;
+use zenoh_buffers::{
+    buffer::SplitBuffer,
+    reader::{HasReader, Reader},
+};
+use zenoh_codec::{RCodec, Zenoh080};
+use zenoh_protocol::{
+    common::ZExtBody,
+    core::{Locator, WhatAmI, ZenohIdProto},
+    network::{oam::id::OAM_LINKSTATE, NetworkBody, NetworkMessage},
+};
+
+use crate::{
+    conversation,
+    header_field::{FieldKind, HeaderFieldMap, Registration},
+    tree::TreeArgs,
+    wireshark::set_generated,
+};
+
+pub const PREFIX: &str = "zenoh.linkstate";
+
+/// Options of a link state telling which optional fields it carries, see
+/// `zenoh::net::codec::linkstate`.
+mod options {
+    pub const ZID: u64 = 1;
+    pub const WHATAMI: u64 = 1 << 1;
+    pub const LOCATORS: u64 = 1 << 2;
+    pub const LINK_WEIGHTS: u64 = 1 << 3;
+}
+
+/// State of a node as gossiped by a router or peer in the network OAMs of the link-state
+/// protocol.
+///
+/// Nodes are identified by their index in the graph of the sender (PSID), 0 being the sender
+/// itself. Their ZID, WhatAmI and locators are only sent until the receiver knows them.
+#[derive(Debug)]
+pub(crate) struct LinkState {
+    pub psid: u64,
+    pub sn: u64,
+    pub zid: Option<ZenohIdProto>,
+    pub whatami: Option<WhatAmI>,
+    pub locators: Option<Vec<Locator>>,
+    /// PSIDs of the nodes linked to this one.
+    pub links: Vec<u64>,
+    /// Weights of the links, in the same order.
+    pub link_weights: Option<Vec<u16>>,
+}
+
+/// Decodes the link states carried by a network message, or returns `None` if it isn't a
+/// link-state OAM or its body can't be decoded.
+pub(crate) fn link_states(msg: &NetworkMessage) -> Option<Vec<LinkState>> {
+    let NetworkBody::OAM(oam) = &msg.body else {
+        return None;
+    };
+    let ZExtBody::ZBuf(zbuf) = &oam.body else {
+        return None;
+    };
+    if oam.id != OAM_LINKSTATE {
+        return None;
+    }
+
+    let bytes = zbuf.contiguous();
+    let mut reader = bytes.reader();
+    let states = decode_list(&mut reader)?;
+    if reader.can_read() {
+        return None;
+    }
+    Some(states)
+}
+
+fn decode_list<R: Reader>(reader: &mut R) -> Option<Vec<LinkState>> {
+    let codec = Zenoh080::new();
+    let len: usize = codec.read(&mut *reader).ok()?;
+    (0..len).map(|_| decode_state(&mut *reader)).collect()
+}
+
+fn decode_state<R: Reader>(reader: &mut R) -> Option<LinkState> {
+    let codec = Zenoh080::new();
+    let opts: u64 = codec.read(&mut *reader).ok()?;
+    let psid: u64 = codec.read(&mut *reader).ok()?;
+    let sn: u64 = codec.read(&mut *reader).ok()?;
+    let zid = if opts & options::ZID != 0 {
+        Some(codec.read(&mut *reader).ok()?)
+    } else {
+        None
+    };
+    let whatami = if opts & options::WHATAMI != 0 {
+        let whatami: u8 = codec.read(&mut *reader).ok()?;
+        Some(WhatAmI::try_from(whatami).ok()?)
+    } else {
+        None
+    };
+    let locators = if opts & options::LOCATORS != 0 {
+        Some(codec.read(&mut *reader).ok()?)
+    } else {
+        None
+    };
+    let len: usize = codec.read(&mut *reader).ok()?;
+    let links = (0..len)
+        .map(|_| codec.read(&mut *reader).ok())
+        .collect::<Option<Vec<u64>>>()?;
+    let link_weights = if opts & options::LINK_WEIGHTS != 0 {
+        Some(
+            (0..len)
+                .map(|_| codec.read(&mut *reader).ok())
+                .collect::<Option<Vec<u16>>>()?,
+        )
+    } else {
+        None
+    };
+
+    Some(LinkState {
+        psid,
+        sn,
+        zid,
+        whatami,
+        locators,
+        links,
+        link_weights,
+    })
+}
+
+impl Registration for LinkState {
+    fn generate_hf_map(prefix: &str) -> HeaderFieldMap {
+        [
+            ("node", "Node", FieldKind::Branch),
+            ("node.psid", "PSID", FieldKind::Number),
+            ("node.sn", "Sequence Number", FieldKind::Number),
+            ("node.zid", "ZID", FieldKind::Text),
+            ("node.whatami", "WhatAmI", FieldKind::Text),
+            ("node.locator", "Locator", FieldKind::Text),
+            ("node.link", "Link", FieldKind::Branch),
+            ("node.link.psid", "PSID", FieldKind::Number),
+            ("node.link.zid", "ZID", FieldKind::Text),
+            ("node.link.weight", "Weight", FieldKind::Number),
+        ]
+        .into_iter()
+        .fold(
+            HeaderFieldMap::new().add(prefix.to_string(), "Link States", FieldKind::Branch),
+            |hf_map, (key, name, kind)| hf_map.add(format!("{prefix}.{key}"), name, kind),
+        )
+    }
+
+    fn generate_subtree_names(prefix: &str) -> Vec<String> {
+        vec![
+            prefix.to_string(),
+            format!("{prefix}.node"),
+            format!("{prefix}.node.link"),
+        ]
+    }
+}
+
+/// Add the link states of a network OAM of this packet as a "Link States" subtree with one
+/// subtree per node. Nodes and links are named by their ZID, resolved from the previous link
+/// states of the sender when not carried.
+pub(crate) unsafe fn add_link_states(
+    pinfo: *mut epan_sys::_packet_info,
+    args: &TreeArgs,
+    msg: &NetworkMessage,
+) -> Result<()> {
+    let Some(states) = link_states(msg) else {
+        return Ok(());
+    };
+
+    let tree = args.make_subtree(PREFIX, &format!("Link States, Nodes: {}", states.len()))?;
+    let tree = TreeArgs { length: 0, ..tree };
+    for state in &states {
+        let carried_zid = state.zid.map(|zid| zid.to_string());
+        let zid = carried_zid
+            .clone()
+            .or_else(|| conversation::resolve_psid(pinfo, state.psid));
+        let whatami = state.whatami.map(|whatami| whatami.to_string());
+
+        let mut name = format!("Node {}", zid.as_deref().unwrap_or("unknown"));
+        if let Some(whatami) = &whatami {
+            name += &format!(" ({whatami})");
+        }
+        name += &format!(
+            ", PSID: {}, SN: {}, Links: {}",
+            state.psid,
+            state.sn,
+            state.links.len()
+        );
+        let node = tree.make_subtree(&format!("{PREFIX}.node"), &name)?;
+
+        for (key, value) in [("psid", state.psid), ("sn", state.sn)] {
+            epan_sys::proto_tree_add_uint64(
+                node.tree,
+                node.get_hf(&format!("{PREFIX}.node.{key}"))?,
+                node.tvb,
+                node.start as _,
+                0,
+                value,
+            );
+        }
+        if let Some(zid) = &zid {
+            let ti = add_string(&node, &format!("{PREFIX}.node.zid"), zid)?;
+            if carried_zid.is_none() {
+                set_generated(ti);
+            }
+        }
+        if let Some(whatami) = &whatami {
+            add_string(&node, &format!("{PREFIX}.node.whatami"), whatami)?;
+        }
+        for locator in state.locators.iter().flatten() {
+            add_string(&node, &format!("{PREFIX}.node.locator"), locator.as_str())?;
+        }
+
+        for (i, psid) in state.links.iter().enumerate() {
+            let link_zid = conversation::resolve_psid(pinfo, *psid);
+            let weight = state
+                .link_weights
+                .as_ref()
+                .and_then(|weights| weights.get(i));
+
+            let mut name = format!(
+                "Link to {}, PSID: {psid}",
+                link_zid.as_deref().unwrap_or("unknown")
+            );
+            if let Some(weight) = weight {
+                name += &format!(", Weight: {weight}");
+            }
+            let link = node.make_subtree(&format!("{PREFIX}.node.link"), &name)?;
+            epan_sys::proto_tree_add_uint64(
+                link.tree,
+                link.get_hf(&format!("{PREFIX}.node.link.psid"))?,
+                link.tvb,
+                link.start as _,
+                0,
+                *psid,
+            );
+            if let Some(link_zid) = &link_zid {
+                set_generated(add_string(
+                    &link,
+                    &format!("{PREFIX}.node.link.zid"),
+                    link_zid,
+                )?);
+            }
+            if let Some(weight) = weight {
+                epan_sys::proto_tree_add_uint64(
+                    link.tree,
+                    link.get_hf(&format!("{PREFIX}.node.link.weight"))?,
+                    link.tvb,
+                    link.start as _,
+                    0,
+                    *weight as _,
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+unsafe fn add_string(
+    args: &TreeArgs,
+    key: &str,
+    value: &str,
+) -> Result<*mut epan_sys::_proto_node> {
+    let value = CString::new(value)?;
+    Ok(epan_sys::proto_tree_add_string(
+        args.tree,
+        args.get_hf(key)?,
+        args.tvb,
+        args.start as _,
+        0,
+        value.as_ptr(),
+    ))
+}
@@ -0,0 +1,149 @@
+use std::ffi::CString;
+
+use anyhow::Result;
+use zenoh_buffers::buffer::{Buffer, SplitBuffer};
+use zenoh_protocol::{common::ZExtBody, network::oam::id::OAM_LINKSTATE, transport::oam::OamId};
+
+use crate::{
+    header_field::{FieldKind, HeaderFieldMap},
+    tree::TreeArgs,
+};
+
+/// Names of the encodings of an OAM body, see `zenoh_protocol::common::ZExtBody`.
+const ENCODINGS: &[(u32, &str)] = &[(0, "Unit"), (1, "Z64"), (2, "ZBuf")];
+
+/// Decoder of the body of a known OAM, showing its content as fields of their own.
+pub(crate) trait OamDecoder: Sync {
+    /// Fields of the decoded body, registered under the prefix of the OAM, e.g.
+    /// `zenoh.transport.oam.<kind>.*`.
+    fn generate_hf_map(&self, prefix: &str) -> HeaderFieldMap;
+    fn generate_subtree_names(&self, prefix: &str) -> Vec<String>;
+    /// Add the decoded body below the OAM, or fail if it doesn't decode, in which case only the
+    /// raw body is shown.
+    fn add_to_tree(&self, body: &ZExtBody, prefix: &str, args: &TreeArgs) -> Result<()>;
+}
+
+/// An OAM id with a known meaning.
+pub(crate) struct OamKind {
+    pub id: OamId,
+    pub name: &'static str,
+    /// Decoder of its body, or `None` if it's only shown raw here.
+    pub decoder: Option<&'static dyn OamDecoder>,
+}
+
+/// Known OAMs of transport messages, which are exchanged between the two ends of a link. Zenoh
+/// doesn't define any yet; new ones go here along with their decoder.
+pub(crate) const TRANSPORT_OAMS: &[OamKind] = &[];
+
+/// Known OAMs of network messages, which are routed between nodes.
+pub(crate) const NETWORK_OAMS: &[OamKind] = &[OamKind {
+    id: OAM_LINKSTATE,
+    name: "Link State",
+    // Decoded along with the conversation, which names the nodes, see `linkstate`.
+    decoder: None,
+}];
+
+/// Fields of an OAM with the given known kinds: its id, the encoding of its body and its raw
+/// value, i.e. a Z64 integer or ZBuf bytes, followed by the fields of every decoder.
+pub(crate) fn generate_hf_map(prefix: &str, kinds: &[OamKind]) -> HeaderFieldMap {
+    let hf_map = [
+        ("id", "Id", FieldKind::Number),
+        ("encoding", "Encoding", FieldKind::Enum(ENCODINGS)),
+        ("value", "Value", FieldKind::Number),
+        ("length", "Length", FieldKind::Number),
+        ("body", "Body", FieldKind::Bytes),
+    ]
+    .into_iter()
+    .fold(HeaderFieldMap::new(), |hf_map, (key, name, kind)| {
+        hf_map.add(format!("{prefix}.{key}"), name, kind)
+    });
+    kinds
+        .iter()
+        .filter_map(|kind| kind.decoder)
+        .fold(hf_map, |mut hf_map, decoder| {
+            hf_map.extend(decoder.generate_hf_map(prefix));
+            hf_map
+        })
+}
+
+pub(crate) fn generate_subtree_names(prefix: &str, kinds: &[OamKind]) -> Vec<String> {
+    kinds
+        .iter()
+        .filter_map(|kind| kind.decoder)
+        .flat_map(|decoder| decoder.generate_subtree_names(prefix))
+        .collect()
+}
+
+/// Add the id and body of an OAM, named after its kind if known, and sum them up in the label of
+/// the OAM subtree, e.g. ", Id: 1 (Link State), Encoding: ZBuf, Length: 42".
+pub(crate) unsafe fn add_to_tree(
+    prefix: &str,
+    args: &TreeArgs,
+    kinds: &[OamKind],
+    id: OamId,
+    body: &ZExtBody,
+) -> Result<()> {
+    let kind = kinds.iter().find(|kind| kind.id == id);
+    let mut summary = match kind {
+        Some(kind) => format!(", Id: {id} ({})", kind.name),
+        None => format!(", Id: {id} (unknown)"),
+    };
+
+    add_uint(args, &format!("{prefix}.id"), id as _)?;
+    let encoding = match body {
+        ZExtBody::Unit => 0,
+        ZExtBody::Z64(_) => 1,
+        ZExtBody::ZBuf(_) => 2,
+    };
+    epan_sys::proto_tree_add_uint(
+        args.tree,
+        args.get_hf(&format!("{prefix}.encoding"))?,
+        args.tvb,
+        args.start as _,
+        0,
+        encoding,
+    );
+    match body {
+        ZExtBody::Unit => summary += ", Encoding: Unit",
+        ZExtBody::Z64(value) => {
+            add_uint(args, &format!("{prefix}.value"), *value)?;
+            summary += &format!(", Encoding: Z64, Value: {value}");
+        }
+        ZExtBody::ZBuf(zbuf) => {
+            let bytes = zbuf.contiguous();
+            add_uint(args, &format!("{prefix}.length"), zbuf.len() as _)?;
+            epan_sys::proto_tree_add_bytes_with_length(
+                args.tree,
+                args.get_hf(&format!("{prefix}.body"))?,
+                args.tvb,
+                args.start as _,
+                0,
+                bytes.as_ptr(),
+                bytes.len() as _,
+            );
+            summary += &format!(", Encoding: ZBuf, Length: {}", zbuf.len());
+        }
+    }
+
+    if let Some(decoder) = kind.and_then(|kind| kind.decoder) {
+        if decoder.add_to_tree(body, prefix, args).is_err() {
+            summary += " (malformed)";
+        }
+    }
+
+    let summary = CString::new(summary)?;
+    epan_sys::proto_item_append_text(epan_sys::proto_tree_get_parent(args.tree), summary.as_ptr());
+    Ok(())
+}
+
+unsafe fn add_uint(args: &TreeArgs, key: &str, value: u64) -> Result<()> {
+    epan_sys::proto_tree_add_uint64(
+        args.tree,
+        args.get_hf(key)?,
+        args.tvb,
+        args.start as _,
+        0,
+        value,
+    );
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::tap::{TapRecord, TAP_NAME};
+use epan_sys::srt_stat_table;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{c_int, c_void, CStr, CString},
+};
+
+/// Index of the tables in the array of the SRT statistics.
+const KEYEXPR_TABLE: usize = 0;
+const QUERYABLE_TABLE: usize = 1;
+
+/// Register the service response time statistics of queries, i.e. the time from a Request to
+/// each of its Responses, by key expression of the Request and by queryable.
+pub fn register_srt(proto_id: i32) {
+    unsafe {
+        epan_sys::register_srt_table(
+            proto_id,
+            TAP_NAME.as_ptr(),
+            0,
+            Some(srt_packet),
+            Some(srt_init),
+            None,
+        );
+    }
+}
+
+/// Rows of a table, by procedure name. Rows are added as key expressions and queryables show
+/// up, which `init_srt_table_row` supports by growing the table.
+type Rows = HashMap<String, c_int>;
+
+thread_local! {
+    /// Rows of the tables of each SRT statistics, keyed by the address of its array of tables,
+    /// which lives as long as the statistics. Tables don't free their specific data, so the rows
+    /// are kept here instead, and replaced whenever the tables are initialised again.
+    static ROWS: RefCell<HashMap<usize, [Rows; 2]>> = RefCell::new(HashMap::new());
+}
+
+unsafe extern "C" fn srt_init(
+    _srt: *mut epan_sys::register_srt_t,
+    srt_array: *mut epan_sys::GArray,
+) {
+    for (name, short_name, column) in [
+        (
+            c"Zenoh Queries by Key Expression",
+            c"Key Expressions",
+            c"Key Expression",
+        ),
+        (
+            c"Zenoh Queries by Queryable",
+            c"Queryables",
+            c"Queryable ZID",
+        ),
+    ] {
+        epan_sys::init_srt_table(
+            name.as_ptr(),
+            short_name.as_ptr(),
+            srt_array,
+            0,
+            column.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null_mut(),
+        );
+    }
+    ROWS.with_borrow_mut(|rows| rows.insert(srt_array as usize, Default::default()));
+}
+
+unsafe extern "C" fn srt_packet(
+    pss: *mut c_void,
+    pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let record = &*(data as *const TapRecord);
+    if record.network.is_null()
+        || CStr::from_ptr(record.network) != c"Response"
+        || record.request_frame == 0
+    {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    }
+
+    let queryable = [record.responder_zid, record.src_zid]
+        .into_iter()
+        .find(|zid| !zid.is_null())
+        .map_or("Unknown".into(), |zid| {
+            CStr::from_ptr(zid).to_string_lossy()
+        });
+    let keyexpr = CStr::from_ptr(record.request_keyexpr).to_string_lossy();
+
+    let srt_array = (*(pss as *mut epan_sys::srt_data_t)).srt_array;
+    let tables = (*srt_array).data as *mut *mut srt_stat_table;
+    ROWS.with_borrow_mut(|rows| {
+        let rows = rows.entry(srt_array as usize).or_default();
+        for (index, procedure) in [(KEYEXPR_TABLE, keyexpr), (QUERYABLE_TABLE, queryable)] {
+            let table = *tables.add(index);
+            let rows = &mut rows[index];
+            let next = rows.len() as c_int;
+            let row = *rows.entry(procedure.to_string()).or_insert_with(|| {
+                let procedure = CString::new(procedure.as_bytes()).unwrap();
+                epan_sys::init_srt_table_row(table, next, procedure.as_ptr());
+                next
+            });
+            epan_sys::add_srt_table_data(table, row, &record.request_ts, pinfo);
+        }
+    });
+
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
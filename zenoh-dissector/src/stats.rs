@@ -0,0 +1,297 @@
+use crate::{
+    tap::{TapRecord, TAP_NAME},
+    topology::{Change, Event, Topology, When, TOPOLOGY_TAP_NAME},
+};
+use epan_sys::stats_tree;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{c_char, c_int, c_void, CStr, CString},
+};
+
+/// Names of the priorities, indexed by `zenoh_protocol::core::Priority`.
+pub(crate) const PRIORITY_NAMES: [&CStr; 8] = [
+    c"Control (0)",
+    c"RealTime (1)",
+    c"InteractiveHigh (2)",
+    c"InteractiveLow (3)",
+    c"DataHigh (4)",
+    c"Data (5)",
+    c"DataLow (6)",
+    c"Background (7)",
+];
+
+/// Ids of the "Messages", "Transport Bytes" and "Network Bytes" root nodes of the Message Types
+/// tree. Bytes have a root per layer, as the bytes of a Frame are also those of its network
+/// messages.
+static mut MESSAGES_NODE: c_int = -1;
+static mut TRANSPORT_BYTES_NODE: c_int = -1;
+static mut NETWORK_BYTES_NODE: c_int = -1;
+
+/// Ids of the "Messages" and "Payload Bytes" root nodes of the Key Expressions tree.
+static mut KEYEXPR_MESSAGES_NODE: c_int = -1;
+static mut KEYEXPR_BYTES_NODE: c_int = -1;
+
+/// Ids of the "Nodes", "Links" and "Changes" root nodes of the Topology tree.
+static mut TOPOLOGY_NODES_NODE: c_int = -1;
+static mut TOPOLOGY_LINKS_NODE: c_int = -1;
+static mut TOPOLOGY_CHANGES_NODE: c_int = -1;
+
+thread_local! {
+    /// Topologies rebuilt by each open Topology tree, keyed by the address of the tree, from
+    /// scratch on every retap.
+    static TOPOLOGIES: RefCell<HashMap<usize, Topology>> = RefCell::new(HashMap::new());
+}
+
+/// Register the statistics trees of the `Statistics > Zenoh` menu.
+pub unsafe extern "C" fn register_stats_trees() {
+    let cfg = epan_sys::stats_tree_register_plugin(
+        TAP_NAME.as_ptr(),
+        c"zenoh".as_ptr(),
+        c"Zenoh/Message Types".as_ptr(),
+        0,
+        Some(message_types_packet),
+        Some(message_types_init),
+        None,
+    );
+    epan_sys::stats_tree_set_first_column_name(cfg, c"Message Type".as_ptr());
+
+    let cfg = epan_sys::stats_tree_register_plugin(
+        TAP_NAME.as_ptr(),
+        c"zenoh_keyexpr".as_ptr(),
+        c"Zenoh/Key Expressions".as_ptr(),
+        0,
+        Some(keyexprs_packet),
+        Some(keyexprs_init),
+        None,
+    );
+    epan_sys::stats_tree_set_first_column_name(cfg, c"Key Expression".as_ptr());
+
+    let cfg = epan_sys::stats_tree_register_plugin(
+        TAP_NAME.as_ptr(),
+        c"zenoh_nodes".as_ptr(),
+        c"Zenoh/Nodes".as_ptr(),
+        0,
+        Some(nodes_packet),
+        None,
+        None,
+    );
+    epan_sys::stats_tree_set_first_column_name(cfg, c"Node".as_ptr());
+
+    let cfg = epan_sys::stats_tree_register_plugin(
+        TOPOLOGY_TAP_NAME.as_ptr(),
+        c"zenoh_topology".as_ptr(),
+        c"Zenoh/Topology".as_ptr(),
+        0,
+        Some(topology_packet),
+        Some(topology_init),
+        Some(topology_cleanup),
+    );
+    epan_sys::stats_tree_set_first_column_name(cfg, c"Topology".as_ptr());
+}
+
+unsafe extern "C" fn message_types_init(st: *mut stats_tree) {
+    MESSAGES_NODE = create_node(st, c"Messages", 0);
+    TRANSPORT_BYTES_NODE = create_node(st, c"Transport Bytes", 0);
+    NETWORK_BYTES_NODE = create_node(st, c"Network Bytes", 0);
+}
+
+/// Count a [`TapRecord`] under the Messages root and the Bytes root of its layer: messages count 1
+/// and bytes count the record length. Low-latency network messages, which have no transport
+/// record, also count as transport bytes.
+///
+/// Transport messages go to `Transport > type`. Network messages go to
+/// `Network > type > reliability > priority`, and also to `Zenoh > type > reliability >
+/// priority` when they carry a zenoh message.
+unsafe extern "C" fn message_types_packet(
+    st: *mut stats_tree,
+    _pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let record = &*(data as *const TapRecord);
+    let priority = usize::try_from(record.priority)
+        .ok()
+        .and_then(|p| PRIORITY_NAMES.get(p))
+        .unwrap_or(&c"Unknown");
+
+    let len = record.len as c_int;
+    if !record.network.is_null() && !record.in_frame {
+        let transport = increase(st, c"Transport".as_ptr(), TRANSPORT_BYTES_NODE, true, len);
+        increase(st, record.transport, transport, false, len);
+    }
+
+    let bytes_root = if record.network.is_null() {
+        TRANSPORT_BYTES_NODE
+    } else {
+        NETWORK_BYTES_NODE
+    };
+    for (root, value) in [(MESSAGES_NODE, 1), (bytes_root, len)] {
+        if record.network.is_null() {
+            let transport = increase(st, c"Transport".as_ptr(), root, true, value);
+            increase(st, record.transport, transport, false, value);
+            continue;
+        }
+
+        let layers = [(c"Network", record.network), (c"Zenoh", record.zenoh)];
+        for (layer, name) in layers {
+            if name.is_null() {
+                continue;
+            }
+            let mut parent = increase(st, layer.as_ptr(), root, true, value);
+            parent = increase(st, name, parent, true, value);
+            parent = increase(st, record.reliability, parent, true, value);
+            increase(st, priority.as_ptr(), parent, false, value);
+        }
+    }
+
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
+
+unsafe extern "C" fn keyexprs_init(st: *mut stats_tree) {
+    KEYEXPR_MESSAGES_NODE = create_node(st, c"Messages", 0);
+    KEYEXPR_BYTES_NODE = create_node(st, c"Payload Bytes", 0);
+}
+
+/// Count the data messages of a [`TapRecord`] by key expression, one level per chunk, so that
+/// every chunk sums up the messages and payload bytes of the key expressions below it.
+unsafe extern "C" fn keyexprs_packet(
+    st: *mut stats_tree,
+    _pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let record = &*(data as *const TapRecord);
+    if record.keyexpr.is_null() {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    }
+
+    let keyexpr = CStr::from_ptr(record.keyexpr).to_string_lossy();
+    for (root, value) in [
+        (KEYEXPR_MESSAGES_NODE, 1),
+        (KEYEXPR_BYTES_NODE, record.payload_len as c_int),
+    ] {
+        let mut parent = root;
+        for chunk in keyexpr.split('/') {
+            let chunk = CString::new(chunk).unwrap();
+            // Every chunk may have children, as a key expression can be the prefix of another.
+            parent = increase(st, chunk.as_ptr(), parent, true, value);
+        }
+    }
+
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
+
+/// Count the messages sent by each node, along with the addresses it sent them from and the
+/// sessions it took part in, i.e. the handshakes it started or answered.
+unsafe extern "C" fn nodes_packet(
+    st: *mut stats_tree,
+    pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let record = &*(data as *const TapRecord);
+    if record.in_frame || record.src.is_null() {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    }
+
+    let node = increase(st, record.src, 0, true, 1);
+
+    let addr = CStr::from_ptr(epan_sys::address_to_str((*pinfo).pool, &(*pinfo).src));
+    let addr = match (*pinfo).srcport {
+        0 => addr.to_owned(),
+        port => CString::new(format!("{}:{port}", addr.to_string_lossy())).unwrap(),
+    };
+    let addresses = increase(st, c"Addresses".as_ptr(), node, true, 1);
+    increase(st, addr.as_ptr(), addresses, false, 1);
+
+    let transport = CStr::from_ptr(record.transport);
+    if record.network.is_null() && (transport == c"InitSyn" || transport == c"InitAck") {
+        increase(st, c"Sessions".as_ptr(), node, false, 1);
+    }
+
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
+
+unsafe extern "C" fn topology_init(st: *mut stats_tree) {
+    TOPOLOGY_NODES_NODE = create_node(st, c"Nodes", 0);
+    TOPOLOGY_LINKS_NODE = create_node(st, c"Links", 0);
+    TOPOLOGY_CHANGES_NODE = create_node(st, c"Changes", 0);
+    TOPOLOGIES.with_borrow_mut(|topologies| topologies.insert(st as usize, Topology::new()));
+}
+
+unsafe extern "C" fn topology_cleanup(st: *mut stats_tree) {
+    TOPOLOGIES.with_borrow_mut(|topologies| topologies.remove(&(st as usize)));
+}
+
+/// Rebuild the topology from the [`Event`]s of a message. Nodes count the handshakes and link
+/// states announcing them, links count the times they came up, and every change is listed with
+/// the frame and time it was seen at.
+unsafe extern "C" fn topology_packet(
+    st: *mut stats_tree,
+    pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let events = &*(data as *const Vec<Event>);
+    let when = When::of(pinfo);
+    TOPOLOGIES.with_borrow_mut(|topologies| {
+        let topology = topologies.entry(st as usize).or_insert_with(Topology::new);
+        for event in events {
+            for (when, change) in topology.apply(event, when) {
+                match change {
+                    Change::NodeAdded { zid, .. } => {
+                        let zid = CString::new(zid.as_str()).unwrap();
+                        increase(st, zid.as_ptr(), TOPOLOGY_NODES_NODE, false, 0);
+                    }
+                    Change::LinkUp { a, b } => {
+                        let link = CString::new(format!("{a} -- {b}")).unwrap();
+                        increase(st, link.as_ptr(), TOPOLOGY_LINKS_NODE, false, 1);
+                    }
+                    _ => {}
+                }
+                let text = CString::new(format!("{when}: {change}")).unwrap();
+                increase(st, text.as_ptr(), TOPOLOGY_CHANGES_NODE, false, 1);
+            }
+
+            if let Event::Node { zid, .. } | Event::LinkState { zid, .. } = event {
+                let zid = CString::new(zid.as_str()).unwrap();
+                increase(st, zid.as_ptr(), TOPOLOGY_NODES_NODE, false, 1);
+            }
+        }
+    });
+
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
+
+unsafe fn create_node(st: *mut stats_tree, name: &CStr, parent: c_int) -> c_int {
+    epan_sys::stats_tree_create_node(
+        st,
+        name.as_ptr(),
+        parent,
+        epan_sys::_stat_node_datatype_STAT_DT_INT,
+        true,
+    )
+}
+
+/// Add `value` to the child `name` of `parent`, creating it if needed, and return its id.
+unsafe fn increase(
+    st: *mut stats_tree,
+    name: *const c_char,
+    parent: c_int,
+    with_children: bool,
+    value: c_int,
+) -> c_int {
+    epan_sys::stats_tree_manip_node_int(
+        epan_sys::_manip_node_mode_MN_INCREASE,
+        st,
+        name,
+        parent,
+        with_children,
+        value,
+    )
+}
@@ -0,0 +1,198 @@
+use crate::{
+    conversation,
+    tap::{message_data, network_name},
+    utils::{lowlatency_message_summary, transport_message_summary},
+    MessageBody,
+};
+use std::ffi::c_int;
+use zenoh_buffers::buffer::Buffer;
+use zenoh_protocol::{
+    core::WireExpr,
+    network::{DeclareBody, NetworkBody, NetworkMessage, NetworkMessageExt},
+    transport::{TransportBody, TransportBodyLowLatency},
+};
+
+/// Level of detail of the message summaries of the Info column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Verbosity {
+    /// Message types only, e.g. `Push`.
+    Types,
+    /// Message types with their request or declaration id and key expression, e.g.
+    /// `Request #12 sensors/**`.
+    KeyExprs,
+    /// Key expressions followed by the QoS and payload size, e.g.
+    /// `Push robot/1/odom (QoS D5, 1.2KB)`.
+    Full,
+}
+
+impl Verbosity {
+    /// Returns the verbosity of a value of [`VERBOSITY_PREF_VALUES`].
+    pub(crate) fn from_pref(value: c_int) -> Self {
+        match value {
+            0 => Self::Types,
+            1 => Self::KeyExprs,
+            _ => Self::Full,
+        }
+    }
+}
+
+/// Values of the verbosity preference, terminated by a null entry as Wireshark expects.
+pub(crate) static mut VERBOSITY_PREF_VALUES: [epan_sys::enum_val_t; 4] = [
+    epan_sys::enum_val_t {
+        name: c"types".as_ptr(),
+        description: c"Message types".as_ptr(),
+        value: Verbosity::Types as _,
+    },
+    epan_sys::enum_val_t {
+        name: c"keyexprs".as_ptr(),
+        description: c"Message types and key expressions".as_ptr(),
+        value: Verbosity::KeyExprs as _,
+    },
+    epan_sys::enum_val_t {
+        name: c"full".as_ptr(),
+        description: c"Message types, key expressions, QoS and payload sizes".as_ptr(),
+        value: Verbosity::Full as _,
+    },
+    epan_sys::enum_val_t {
+        name: std::ptr::null(),
+        description: std::ptr::null(),
+        value: 0,
+    },
+];
+
+/// Returns the Info column summaries of a message: one per network message of a Frame, the
+/// network message completed by a Fragment, or the message itself.
+pub(crate) unsafe fn message_summaries(
+    pinfo: *mut epan_sys::_packet_info,
+    body: &MessageBody,
+    reassembled: Option<&NetworkMessage>,
+    verbosity: Verbosity,
+) -> Vec<String> {
+    match body {
+        MessageBody::Transport(msg) => match (&msg.body, reassembled) {
+            (TransportBody::Frame(frame), _) => frame
+                .payload
+                .iter()
+                .map(|msg| network_summary(pinfo, msg, verbosity))
+                .collect(),
+            (TransportBody::Fragment(_), Some(msg)) => {
+                vec![network_summary(pinfo, msg, verbosity)]
+            }
+            _ => vec![transport_message_summary(msg)],
+        },
+        MessageBody::LowLatency(msg) => match &msg.body {
+            TransportBodyLowLatency::Network(msg) => vec![network_summary(pinfo, msg, verbosity)],
+            _ => vec![lowlatency_message_summary(msg)],
+        },
+    }
+}
+
+/// Returns the summary of a network message, see [`Verbosity`].
+unsafe fn network_summary(
+    pinfo: *mut epan_sys::_packet_info,
+    msg: &NetworkMessage,
+    verbosity: Verbosity,
+) -> String {
+    let mut summary = match &msg.body {
+        NetworkBody::Declare(declare) => declare_name(&declare.body).to_string(),
+        body => network_name(body).to_string_lossy().into_owned(),
+    };
+    if verbosity == Verbosity::Types {
+        return summary;
+    }
+
+    let id = match &msg.body {
+        NetworkBody::Request(request) => Some(request.id),
+        NetworkBody::Response(response) => Some(response.rid),
+        NetworkBody::ResponseFinal(response_final) => Some(response_final.rid),
+        NetworkBody::Interest(interest) => Some(interest.id),
+        NetworkBody::Declare(declare) => declare_id(&declare.body),
+        _ => None,
+    };
+    if let Some(id) = id {
+        summary += &format!(" #{id}");
+    }
+
+    let data = message_data(&msg.body);
+    let wire_expr = match &msg.body {
+        NetworkBody::Declare(declare) => declare_wire_expr(&declare.body),
+        NetworkBody::Interest(interest) => interest.wire_expr.as_ref(),
+        _ => data.as_ref().map(|data| data.wire_expr),
+    };
+    if let Some(expr) = wire_expr {
+        let keyexpr =
+            conversation::resolve_keyexpr(pinfo, expr).unwrap_or_else(|| expr.to_string());
+        summary += &format!(" {keyexpr}");
+    }
+    if verbosity == Verbosity::KeyExprs {
+        return summary;
+    }
+
+    // Congestion control: D(rop), B(lock) or F (block first), followed by the priority.
+    let congestion_control = ['D', 'B', 'F']
+        .get(msg.congestion_control() as usize)
+        .unwrap_or(&'?');
+    let mut details = vec![format!(
+        "QoS {congestion_control}{}{}",
+        msg.priority() as u8,
+        if msg.is_express() { "E" } else { "" }
+    )];
+    if let Some(payload) = data.and_then(|data| data.payload) {
+        details.push(human_size(payload.len()));
+    }
+    summary + &format!(" ({})", details.join(", "))
+}
+
+fn declare_name(body: &DeclareBody) -> &'static str {
+    match body {
+        DeclareBody::DeclareKeyExpr(_) => "DeclareKeyExpr",
+        DeclareBody::UndeclareKeyExpr(_) => "UndeclareKeyExpr",
+        DeclareBody::DeclareSubscriber(_) => "DeclareSubscriber",
+        DeclareBody::UndeclareSubscriber(_) => "UndeclareSubscriber",
+        DeclareBody::DeclareQueryable(_) => "DeclareQueryable",
+        DeclareBody::UndeclareQueryable(_) => "UndeclareQueryable",
+        DeclareBody::DeclareToken(_) => "DeclareToken",
+        DeclareBody::UndeclareToken(_) => "UndeclareToken",
+        DeclareBody::DeclareFinal(_) => "DeclareFinal",
+    }
+}
+
+fn declare_id(body: &DeclareBody) -> Option<u32> {
+    match body {
+        DeclareBody::DeclareKeyExpr(decl) => Some(decl.id as u32),
+        DeclareBody::UndeclareKeyExpr(undecl) => Some(undecl.id as u32),
+        DeclareBody::DeclareSubscriber(decl) => Some(decl.id),
+        DeclareBody::UndeclareSubscriber(undecl) => Some(undecl.id),
+        DeclareBody::DeclareQueryable(decl) => Some(decl.id),
+        DeclareBody::UndeclareQueryable(undecl) => Some(undecl.id),
+        DeclareBody::DeclareToken(decl) => Some(decl.id),
+        DeclareBody::UndeclareToken(undecl) => Some(undecl.id),
+        DeclareBody::DeclareFinal(_) => None,
+    }
+}
+
+fn declare_wire_expr(body: &DeclareBody) -> Option<&WireExpr<'static>> {
+    match body {
+        DeclareBody::DeclareKeyExpr(decl) => Some(&decl.wire_expr),
+        DeclareBody::DeclareSubscriber(decl) => Some(&decl.wire_expr),
+        DeclareBody::DeclareQueryable(decl) => Some(&decl.wire_expr),
+        DeclareBody::DeclareToken(decl) => Some(&decl.wire_expr),
+        _ => None,
+    }
+}
+
+/// Returns a size in bytes with a binary unit, e.g. `512B` or `1.2KB`.
+fn human_size(len: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = len as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{len}B")
+    } else {
+        format!("{size:.1}{}", UNITS[unit])
+    }
+}
@@ -0,0 +1,122 @@
+use crate::tap::TapRecord;
+use epan_sys::{conv_filter_type_e, conv_hash_t};
+use std::ffi::{c_char, c_void, CStr};
+
+/// Register the Zenoh conversation and endpoint tables, whose addresses are nodes (a ZID and its
+/// WhatAmI). They are fed by the `zenoh` tap, which is named after the protocol.
+pub fn register_tables(proto_id: i32) {
+    unsafe {
+        epan_sys::register_conversation_table(
+            proto_id,
+            true,
+            Some(conversation_packet),
+            Some(endpoint_packet),
+        );
+    }
+}
+
+static mut CT_INFO: epan_sys::ct_dissector_info_t = epan_sys::ct_dissector_info_t {
+    get_filter_type: Some(conversation_filter_type),
+};
+
+static mut ET_INFO: epan_sys::et_dissector_info_t = epan_sys::et_dissector_info_t {
+    get_filter_type: Some(endpoint_filter_type),
+};
+
+unsafe extern "C" fn conversation_filter_type(
+    _item: *mut epan_sys::conv_item_t,
+    filter: conv_filter_type_e,
+) -> *const c_char {
+    filter_type(filter)
+}
+
+unsafe extern "C" fn endpoint_filter_type(
+    _item: *mut epan_sys::endpoint_item_t,
+    filter: conv_filter_type_e,
+) -> *const c_char {
+    filter_type(filter)
+}
+
+/// Returns the field matching a node address, see `conversation::FIELD_SRC` and its siblings.
+fn filter_type(filter: conv_filter_type_e) -> *const c_char {
+    match filter {
+        epan_sys::conv_filter_type_e_CONV_FT_SRC_ADDRESS => c"zenoh.src".as_ptr(),
+        epan_sys::conv_filter_type_e_CONV_FT_DST_ADDRESS => c"zenoh.dst".as_ptr(),
+        epan_sys::conv_filter_type_e_CONV_FT_ANY_ADDRESS => c"zenoh.node".as_ptr(),
+        _ => epan_sys::CONV_FILTER_INVALID.as_ptr() as _,
+    }
+}
+
+/// Returns the nodes of a record as string addresses, if both are known. Messages carried by a
+/// Frame are skipped, as the Frame already counts their bytes.
+unsafe fn record_addresses(
+    data: *const c_void,
+) -> Option<(&'static TapRecord, epan_sys::address, epan_sys::address)> {
+    let record = &*(data as *const TapRecord);
+    if record.in_frame || record.src.is_null() || record.dst.is_null() {
+        return None;
+    }
+    Some((record, node_address(record.src), node_address(record.dst)))
+}
+
+unsafe fn node_address(node: *const c_char) -> epan_sys::address {
+    epan_sys::address {
+        type_: epan_sys::address_type_AT_STRINGZ as _,
+        len: CStr::from_ptr(node).to_bytes_with_nul().len() as _,
+        data: node as _,
+        priv_: std::ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn conversation_packet(
+    tapdata: *mut c_void,
+    pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let Some((record, src, dst)) = record_addresses(data) else {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    };
+
+    epan_sys::add_conversation_table_data(
+        tapdata as *mut conv_hash_t,
+        &src,
+        &dst,
+        0,
+        0,
+        1,
+        record.len as _,
+        &raw mut (*pinfo).rel_ts,
+        &raw mut (*pinfo).abs_ts,
+        &raw mut CT_INFO,
+        epan_sys::conversation_type_CONVERSATION_NONE,
+    );
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
+
+unsafe extern "C" fn endpoint_packet(
+    tapdata: *mut c_void,
+    _pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let Some((record, src, dst)) = record_addresses(data) else {
+        return epan_sys::tap_packet_status_TAP_PACKET_DONT_REDRAW;
+    };
+
+    for (addr, sender) in [(src, true), (dst, false)] {
+        epan_sys::add_endpoint_table_data(
+            tapdata as *mut conv_hash_t,
+            &addr,
+            0,
+            sender,
+            1,
+            record.len as _,
+            &raw mut ET_INFO,
+            epan_sys::conversation_type_CONVERSATION_NONE,
+        );
+    }
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
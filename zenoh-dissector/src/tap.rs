@@ -0,0 +1,363 @@
+use crate::{conversation, MessageBody};
+use std::{
+    ffi::{c_char, c_int, CStr, CString},
+    ptr, slice,
+};
+use zenoh_buffers::{buffer::Buffer, ZBuf};
+use zenoh_protocol::{
+    core::{Encoding, Reliability, Timestamp, WireExpr},
+    network::{NetworkBody, NetworkMessage, NetworkMessageExt, Response, ResponseFinal},
+    transport::{TransportBody, TransportBodyLowLatency},
+    zenoh::{PushBody, RequestBody, ResponseBody},
+};
+
+/// Name of the tap fed by the dissector, see [`TapRecord`].
+pub const TAP_NAME: &CStr = c"zenoh";
+
+static mut TAP_ID: c_int = -1;
+
+/// Version of the layout of [`TapRecord`], bumped whenever fields are changed. Fields are only
+/// ever appended.
+pub const TAP_RECORD_VERSION: u32 = 1;
+
+/// A message queued to the `zenoh` tap.
+///
+/// Transport messages are queued with a null `network`. The network messages of a Frame or
+/// reassembled from Fragments, and the messages of the low-latency transport, are queued on their
+/// own with their network and zenoh body names.
+///
+/// The layout is public: other plugins may listen to the tap and read the record through
+/// `include/zenoh_tap.h`. Strings are null when not applicable. They live at least as long as
+/// the dissection of the packet.
+#[repr(C)]
+pub struct TapRecord {
+    /// Always [`TAP_RECORD_VERSION`].
+    pub version: u32,
+    /// Name of the transport message, e.g. `InitSyn` or `Frame`.
+    pub transport: *const c_char,
+    /// Name of the network message, e.g. `Push` or `Request`.
+    pub network: *const c_char,
+    /// Name of the zenoh message (`Put`, `Del`, `Query`, `Reply` or `Err`).
+    pub zenoh: *const c_char,
+    /// Length of the encoded message in bytes, or 0 for a network message of a compressed batch.
+    pub len: u32,
+    /// Whether the message is carried by a Frame or Fragments, whose own records already count
+    /// its bytes.
+    pub in_frame: bool,
+    /// ZIDs of the sender and receiver, or null if the handshake of the session wasn't captured.
+    pub src_zid: *const c_char,
+    pub dst_zid: *const c_char,
+    /// Source and destination nodes of the session, i.e. their ZID and WhatAmI.
+    pub src: *const c_char,
+    pub dst: *const c_char,
+    /// Key expression of a Push, Request or Response, resolved against the declarations of the
+    /// capture. Unresolved wire expressions are given as `scope:mapping:suffix`.
+    pub keyexpr: *const c_char,
+    /// Length of the payload of a Push, Request or Response in bytes.
+    pub payload_len: u32,
+    /// Id of a Request, or of the Request a Response or ResponseFinal answers.
+    pub has_request_id: bool,
+    pub request_id: u32,
+    /// Capture time of the packet.
+    pub abs_ts: epan_sys::nstime_t,
+    /// Timestamp of the data (Put or Reply), or else of the network message, as
+    /// `<NTP64 time>/<ZID>`.
+    pub timestamp: *const c_char,
+    /// Name of the reliability of the network message.
+    pub reliability: *const c_char,
+    /// Priority of the network message (0 to 7), -1 for transport messages.
+    pub priority: c_int,
+    /// Congestion control of the network message (0 drop, 1 block, 2 block first), -1 for
+    /// transport messages.
+    pub congestion_control: c_int,
+    /// Whether the network message is express.
+    pub express: bool,
+    /// Frame number of the Request a Response or ResponseFinal answers, or 0 if not captured.
+    pub request_frame: u32,
+    /// Capture time and resolved key expression of that Request.
+    pub request_ts: epan_sys::nstime_t,
+    pub request_keyexpr: *const c_char,
+    /// ZID of the queryable that sent a Response, if announced.
+    pub responder_zid: *const c_char,
+}
+
+/// Register the `zenoh` tap.
+pub fn register_tap() {
+    unsafe {
+        TAP_ID = epan_sys::register_tap(TAP_NAME.as_ptr());
+    }
+}
+
+/// Queue the records of a message of `len` bytes to the `zenoh` tap, if anyone listens to it,
+/// along with the network messages of a Frame, of `network_lens` bytes, or the network message
+/// of `reassembled_len` bytes completed by a Fragment.
+pub unsafe fn queue_message(
+    pinfo: *mut epan_sys::_packet_info,
+    body: &MessageBody,
+    len: usize,
+    network_lens: &[usize],
+    reassembled: Option<&NetworkMessage>,
+    reassembled_len: usize,
+) {
+    if !epan_sys::have_tap_listener(TAP_ID) {
+        return;
+    }
+
+    match body {
+        MessageBody::Transport(msg) => {
+            let transport = transport_name(&msg.body);
+            queue(pinfo, transport_record(transport, len));
+            let (msgs, lens) = match &msg.body {
+                TransportBody::Frame(frame) => (frame.payload.as_slice(), network_lens),
+                _ => (
+                    reassembled.map_or(&[][..], slice::from_ref),
+                    slice::from_ref(&reassembled_len),
+                ),
+            };
+            for (i, msg) in msgs.iter().enumerate() {
+                let len = lens.get(i).copied().unwrap_or_default();
+                let record = TapRecord {
+                    in_frame: true,
+                    ..network_record(pinfo, transport, msg, len)
+                };
+                queue(pinfo, record);
+            }
+        }
+        MessageBody::LowLatency(msg) => match &msg.body {
+            TransportBodyLowLatency::Close(_) => queue(pinfo, transport_record(c"Close", len)),
+            TransportBodyLowLatency::KeepAlive(_) => {
+                queue(pinfo, transport_record(c"KeepAlive", len))
+            }
+            TransportBodyLowLatency::Network(msg) => {
+                queue(pinfo, network_record(pinfo, c"Network", msg, len))
+            }
+        },
+    }
+}
+
+/// Fill in the session of `record`, copy it into the packet scope and queue it. Taps run after
+/// the dissection of the packet, so the record must outlive the caller.
+unsafe fn queue(pinfo: *mut epan_sys::_packet_info, mut record: TapRecord) {
+    let peers = conversation::session_peers(pinfo);
+    record.src_zid = peers.src_zid;
+    record.dst_zid = peers.dst_zid;
+    record.src = peers.src;
+    record.dst = peers.dst;
+    record.abs_ts = (*pinfo).abs_ts;
+
+    let ptr =
+        epan_sys::wmem_alloc((*pinfo).pool, std::mem::size_of::<TapRecord>()) as *mut TapRecord;
+    ptr.write(record);
+    epan_sys::tap_queue_packet(TAP_ID, pinfo, ptr as *const _);
+}
+
+fn transport_record(name: &'static CStr, len: usize) -> TapRecord {
+    TapRecord {
+        version: TAP_RECORD_VERSION,
+        transport: name.as_ptr(),
+        network: ptr::null(),
+        zenoh: ptr::null(),
+        len: len as _,
+        in_frame: false,
+        src_zid: ptr::null(),
+        dst_zid: ptr::null(),
+        src: ptr::null(),
+        dst: ptr::null(),
+        keyexpr: ptr::null(),
+        payload_len: 0,
+        has_request_id: false,
+        request_id: 0,
+        abs_ts: epan_sys::nstime_t { secs: 0, nsecs: 0 },
+        timestamp: ptr::null(),
+        reliability: ptr::null(),
+        priority: -1,
+        congestion_control: -1,
+        express: false,
+        request_frame: 0,
+        request_ts: epan_sys::nstime_t { secs: 0, nsecs: 0 },
+        request_keyexpr: ptr::null(),
+        responder_zid: ptr::null(),
+    }
+}
+
+unsafe fn network_record(
+    pinfo: *mut epan_sys::_packet_info,
+    transport: &'static CStr,
+    msg: &NetworkMessage,
+    len: usize,
+) -> TapRecord {
+    let data = message_data(&msg.body);
+    let keyexpr = data.as_ref().map_or(ptr::null(), |data| {
+        let keyexpr = conversation::resolve_keyexpr(pinfo, data.wire_expr)
+            .unwrap_or_else(|| data.wire_expr.to_string());
+        packet_scoped_c_str(pinfo, keyexpr)
+    });
+    let payload = data.as_ref().and_then(|data| data.payload);
+    let request_id = match &msg.body {
+        NetworkBody::Request(request) => Some(request.id),
+        NetworkBody::Response(response) => Some(response.rid),
+        NetworkBody::ResponseFinal(response_final) => Some(response_final.rid),
+        _ => None,
+    };
+    let request = match &msg.body {
+        NetworkBody::Response(Response { rid, .. })
+        | NetworkBody::ResponseFinal(ResponseFinal { rid, .. }) => {
+            conversation::find_request(pinfo, *rid).as_ref()
+        }
+        _ => None,
+    };
+    let responder_zid = match &msg.body {
+        NetworkBody::Response(Response {
+            ext_respid: Some(respid),
+            ..
+        }) => packet_scoped_c_str(pinfo, respid.zid.to_string()),
+        _ => ptr::null(),
+    };
+    let timestamp = data_timestamp(&msg.body)
+        .map_or(ptr::null(), |ts| packet_scoped_c_str(pinfo, ts.to_string()));
+
+    TapRecord {
+        network: network_name(&msg.body).as_ptr(),
+        zenoh: zenoh_name(&msg.body).map_or(ptr::null(), CStr::as_ptr),
+        keyexpr,
+        payload_len: payload.map_or(0, ZBuf::len) as _,
+        has_request_id: request_id.is_some(),
+        request_id: request_id.unwrap_or_default(),
+        timestamp,
+        reliability: match msg.reliability {
+            Reliability::Reliable => c"Reliable",
+            Reliability::BestEffort => c"Best effort",
+        }
+        .as_ptr(),
+        priority: msg.priority() as _,
+        congestion_control: msg.congestion_control() as _,
+        express: msg.is_express(),
+        request_frame: request.map_or(0, |request| request.frame),
+        request_ts: request.map_or(epan_sys::nstime_t { secs: 0, nsecs: 0 }, |request| {
+            request.ts
+        }),
+        request_keyexpr: request.map_or(ptr::null(), |request| request.keyexpr),
+        responder_zid,
+        ..transport_record(transport, len)
+    }
+}
+
+unsafe fn packet_scoped_c_str(pinfo: *mut epan_sys::_packet_info, s: String) -> *const c_char {
+    let s = CString::new(s.replace('\0', "")).unwrap();
+    epan_sys::wmem_strdup((*pinfo).pool, s.as_ptr())
+}
+
+/// Returns the timestamp of the data of a message, falling back to its network timestamp.
+fn data_timestamp(body: &NetworkBody) -> Option<&Timestamp> {
+    fn put_timestamp(body: &PushBody) -> Option<&Timestamp> {
+        match body {
+            PushBody::Put(put) => put.timestamp.as_ref(),
+            PushBody::Del(del) => del.timestamp.as_ref(),
+        }
+    }
+    match body {
+        NetworkBody::Push(push) => {
+            put_timestamp(&push.payload).or(push.ext_tstamp.as_ref().map(|ext| &ext.timestamp))
+        }
+        NetworkBody::Request(request) => request.ext_tstamp.as_ref().map(|ext| &ext.timestamp),
+        NetworkBody::Response(response) => match &response.payload {
+            ResponseBody::Reply(reply) => put_timestamp(&reply.payload),
+            ResponseBody::Err(_) => None,
+        }
+        .or(response.ext_tstamp.as_ref().map(|ext| &ext.timestamp)),
+        NetworkBody::ResponseFinal(response_final) => {
+            response_final.ext_tstamp.as_ref().map(|ext| &ext.timestamp)
+        }
+        _ => None,
+    }
+}
+
+/// Key expression, payload and encoding of a message carrying data.
+pub(crate) struct Data<'a> {
+    pub wire_expr: &'a WireExpr<'static>,
+    pub payload: Option<&'a ZBuf>,
+    pub encoding: Option<&'a Encoding>,
+}
+
+/// Returns the data of a Push, Request or Response. Payload and encoding are absent from a Del
+/// and from a Query without body.
+pub(crate) fn message_data(body: &NetworkBody) -> Option<Data<'_>> {
+    fn push_data<'a>(wire_expr: &'a WireExpr<'static>, body: &'a PushBody) -> Data<'a> {
+        match body {
+            PushBody::Put(put) => Data {
+                wire_expr,
+                payload: Some(&put.payload),
+                encoding: Some(&put.encoding),
+            },
+            PushBody::Del(_) => Data {
+                wire_expr,
+                payload: None,
+                encoding: None,
+            },
+        }
+    }
+    match body {
+        NetworkBody::Push(push) => Some(push_data(&push.wire_expr, &push.payload)),
+        NetworkBody::Request(request) => {
+            let RequestBody::Query(query) = &request.payload;
+            Some(Data {
+                wire_expr: &request.wire_expr,
+                payload: query.ext_body.as_ref().map(|body| &body.payload),
+                encoding: query.ext_body.as_ref().map(|body| &body.encoding),
+            })
+        }
+        NetworkBody::Response(response) => Some(match &response.payload {
+            ResponseBody::Reply(reply) => push_data(&response.wire_expr, &reply.payload),
+            ResponseBody::Err(err) => Data {
+                wire_expr: &response.wire_expr,
+                payload: Some(&err.payload),
+                encoding: Some(&err.encoding),
+            },
+        }),
+        _ => None,
+    }
+}
+
+fn transport_name(body: &TransportBody) -> &'static CStr {
+    match body {
+        TransportBody::OAM(_) => c"OAM",
+        TransportBody::InitSyn(_) => c"InitSyn",
+        TransportBody::InitAck(_) => c"InitAck",
+        TransportBody::OpenSyn(_) => c"OpenSyn",
+        TransportBody::OpenAck(_) => c"OpenAck",
+        TransportBody::Close(_) => c"Close",
+        TransportBody::KeepAlive(_) => c"KeepAlive",
+        TransportBody::Frame(_) => c"Frame",
+        TransportBody::Fragment(_) => c"Fragment",
+        TransportBody::Join(_) => c"Join",
+    }
+}
+
+pub(crate) fn network_name(body: &NetworkBody) -> &'static CStr {
+    match body {
+        NetworkBody::Push(_) => c"Push",
+        NetworkBody::Request(_) => c"Request",
+        NetworkBody::Response(_) => c"Response",
+        NetworkBody::ResponseFinal(_) => c"ResponseFinal",
+        NetworkBody::Interest(_) => c"Interest",
+        NetworkBody::Declare(_) => c"Declare",
+        NetworkBody::OAM(_) => c"OAM",
+    }
+}
+
+pub(crate) fn zenoh_name(body: &NetworkBody) -> Option<&'static CStr> {
+    match body {
+        NetworkBody::Push(push) => Some(match push.payload {
+            PushBody::Put(_) => c"Put",
+            PushBody::Del(_) => c"Del",
+        }),
+        NetworkBody::Request(request) => Some(match request.payload {
+            RequestBody::Query(_) => c"Query",
+        }),
+        NetworkBody::Response(response) => Some(match response.payload {
+            ResponseBody::Reply(_) => c"Reply",
+            ResponseBody::Err(_) => c"Err",
+        }),
+        _ => None,
+    }
+}
@@ -0,0 +1,795 @@
+use crate::{conversation, linkstate, MessageBody};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::{c_char, c_int, c_void, CStr},
+    fmt::{self, Write},
+};
+use zenoh_protocol::{
+    core::WhatAmI,
+    network::NetworkMessage,
+    transport::{TransportBody, TransportBodyLowLatency, TransportMessage},
+};
+
+/// Name of the tap fed with the [`Event`]s of each message.
+pub const TOPOLOGY_TAP_NAME: &CStr = c"zenoh_topology";
+
+static mut TOPOLOGY_TAP_ID: c_int = -1;
+
+/// What a message tells about the graph of routers, peers and clients.
+#[derive(Debug)]
+pub(crate) enum Event {
+    /// A node announced itself in an InitSyn, InitAck or Join.
+    Node { zid: String, whatami: WhatAmI },
+    /// A session between two nodes was opened by an OpenAck, or closed.
+    Session { a: String, b: String, up: bool },
+    /// A node was gossiped in a link-state OAM, along with the nodes it's linked to. Links to
+    /// nodes whose PSID can't be resolved to a ZID are left out.
+    LinkState {
+        zid: String,
+        whatami: Option<WhatAmI>,
+        sn: u64,
+        links: BTreeSet<String>,
+    },
+}
+
+/// Register the `zenoh_topology` tap and its `-z zenoh,topology` tshark statistics.
+pub fn register_tap() {
+    static mut UI: epan_sys::stat_tap_ui = epan_sys::stat_tap_ui {
+        group: epan_sys::register_stat_group_t_REGISTER_STAT_GROUP_GENERIC,
+        title: c"Zenoh Topology".as_ptr(),
+        cli_string: c"zenoh,topology".as_ptr(),
+        tap_init_cb: Some(cli_init),
+        nparams: 0,
+        params: std::ptr::null_mut(),
+    };
+    unsafe {
+        TOPOLOGY_TAP_ID = epan_sys::register_tap(TOPOLOGY_TAP_NAME.as_ptr());
+        epan_sys::register_stat_tap_ui(&raw mut UI, std::ptr::null_mut());
+    }
+}
+
+/// Queue the [`Event`]s of a message to the `zenoh_topology` tap, if anyone listens to it.
+///
+/// Sessions are named by the ZIDs of their handshake, and link states by the PSIDs resolved
+/// from the previous link states of their sender, both tracked by the conversation.
+pub unsafe fn queue_events(
+    pinfo: *mut epan_sys::_packet_info,
+    body: &MessageBody,
+    reassembled: Option<&NetworkMessage>,
+) {
+    if !epan_sys::have_tap_listener(TOPOLOGY_TAP_ID) {
+        return;
+    }
+
+    let mut events = Vec::new();
+    let node = |zid: &dyn ToString, whatami| Event::Node {
+        zid: zid.to_string(),
+        whatami,
+    };
+    let session = |up| {
+        let peers = conversation::session_peers(pinfo);
+        (!peers.src_zid.is_null() && !peers.dst_zid.is_null()).then(|| Event::Session {
+            a: CStr::from_ptr(peers.src_zid).to_string_lossy().into_owned(),
+            b: CStr::from_ptr(peers.dst_zid).to_string_lossy().into_owned(),
+            up,
+        })
+    };
+    match body {
+        MessageBody::Transport(TransportMessage { body }) => match body {
+            TransportBody::InitSyn(init) => events.push(node(&init.zid, init.whatami)),
+            TransportBody::InitAck(init) => events.push(node(&init.zid, init.whatami)),
+            TransportBody::Join(join) => events.push(node(&join.zid, join.whatami)),
+            TransportBody::OpenAck(_) => events.extend(session(true)),
+            TransportBody::Close(_) if conversation::closes_session(pinfo) => {
+                events.extend(session(false))
+            }
+            _ => {}
+        },
+        MessageBody::LowLatency(msg) => {
+            if let TransportBodyLowLatency::Close(_) = msg.body {
+                if conversation::closes_session(pinfo) {
+                    events.extend(session(false));
+                }
+            }
+        }
+    }
+
+    let msgs = conversation::network_messages(body)
+        .iter()
+        .chain(reassembled);
+    for state in msgs.filter_map(linkstate::link_states).flatten() {
+        let Some(zid) = state
+            .zid
+            .map(|zid| zid.to_string())
+            .or_else(|| conversation::resolve_psid(pinfo, state.psid))
+        else {
+            continue;
+        };
+        let links = state
+            .links
+            .iter()
+            .filter_map(|psid| conversation::resolve_psid(pinfo, *psid))
+            .collect();
+        events.push(Event::LinkState {
+            zid,
+            whatami: state.whatami,
+            sn: state.sn,
+            links,
+        });
+    }
+    if events.is_empty() {
+        return;
+    }
+
+    // Taps run after the dissection of the packet, so the events are dropped with its scope.
+    unsafe extern "C" fn drop_events(
+        _allocator: *mut epan_sys::wmem_allocator_t,
+        _event: epan_sys::wmem_cb_event_t,
+        user_data: *mut c_void,
+    ) -> bool {
+        std::ptr::drop_in_place(user_data as *mut Vec<Event>);
+        false
+    }
+    let ptr =
+        epan_sys::wmem_alloc((*pinfo).pool, std::mem::size_of::<Vec<Event>>()) as *mut Vec<Event>;
+    ptr.write(events);
+    epan_sys::wmem_register_callback((*pinfo).pool, Some(drop_events), ptr as _);
+    epan_sys::tap_queue_packet(TOPOLOGY_TAP_ID, pinfo, ptr as *const _);
+}
+
+/// The frame in which a change was seen, with its time relative to the first frame and its
+/// absolute time, in seconds.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct When {
+    pub frame: u32,
+    pub rel_time: f64,
+    pub abs_time: f64,
+}
+
+impl When {
+    pub unsafe fn of(pinfo: *const epan_sys::packet_info) -> Self {
+        When {
+            frame: (*pinfo).num,
+            rel_time: epan_sys::nstime_to_sec(&(*pinfo).rel_ts),
+            abs_time: epan_sys::nstime_to_sec(&(*pinfo).abs_ts),
+        }
+    }
+}
+
+impl fmt::Display for When {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Frame {} ({:.6} s)", self.frame, self.rel_time)
+    }
+}
+
+#[derive(Debug)]
+struct Node {
+    whatami: Option<WhatAmI>,
+    first_seen: When,
+    /// Sequence number and links of the last link state gossiped for the node.
+    sn: Option<u64>,
+    links: BTreeSet<String>,
+}
+
+/// A link between two nodes, up while a session is open between them or while a link state of
+/// either of them lists the other.
+#[derive(Debug)]
+struct Link {
+    sessions: u32,
+    up: bool,
+    since: When,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Change {
+    NodeAdded {
+        zid: String,
+        whatami: Option<WhatAmI>,
+    },
+    RoleChanged {
+        zid: String,
+        whatami: WhatAmI,
+    },
+    LinkUp {
+        a: String,
+        b: String,
+    },
+    LinkDown {
+        a: String,
+        b: String,
+    },
+}
+
+impl Change {
+    /// Name of the change in the JSON export.
+    fn kind(&self) -> &'static str {
+        match self {
+            Change::NodeAdded { .. } => "node_added",
+            Change::RoleChanged { .. } => "role_changed",
+            Change::LinkUp { .. } => "link_up",
+            Change::LinkDown { .. } => "link_down",
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::NodeAdded { zid, whatami } => {
+                write!(f, "Node {} added", node_label(zid, *whatami))
+            }
+            Change::RoleChanged { zid, whatami } => write!(f, "Node {zid} is now a {whatami}"),
+            Change::LinkUp { a, b } => write!(f, "Link {a} -- {b} up"),
+            Change::LinkDown { a, b } => write!(f, "Link {a} -- {b} down"),
+        }
+    }
+}
+
+/// Names a node by its ZID followed by its WhatAmI, if known, e.g. `1a2b3c (router)`.
+pub(crate) fn node_label(zid: &str, whatami: Option<WhatAmI>) -> String {
+    match whatami {
+        Some(whatami) => format!("{zid} ({whatami})"),
+        None => zid.to_string(),
+    }
+}
+
+/// The graph of nodes and links rebuilt from the [`Event`]s of a capture, along with the log of
+/// its changes.
+#[derive(Debug)]
+pub(crate) struct Topology {
+    nodes: BTreeMap<String, Node>,
+    /// Links keyed by the ZIDs of their nodes, the lowest first.
+    links: BTreeMap<(String, String), Link>,
+    changes: Vec<(When, Change)>,
+}
+
+impl Topology {
+    pub const fn new() -> Self {
+        Topology {
+            nodes: BTreeMap::new(),
+            links: BTreeMap::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// Apply an event seen at `when` and return the changes it made, also appended to the log.
+    pub fn apply(&mut self, event: &Event, when: When) -> &[(When, Change)] {
+        let logged = self.changes.len();
+        match event {
+            Event::Node { zid, whatami } => self.add_node(zid, Some(*whatami), when),
+            Event::Session { a, b, up } => {
+                self.add_node(a, None, when);
+                self.add_node(b, None, when);
+                let link = self.links.entry(link_key(a, b)).or_insert(Link {
+                    sessions: 0,
+                    up: false,
+                    since: when,
+                });
+                link.sessions = match up {
+                    true => link.sessions + 1,
+                    false => link.sessions.saturating_sub(1),
+                };
+                self.refresh_link(a, b, when);
+            }
+            Event::LinkState {
+                zid,
+                whatami,
+                sn,
+                links,
+            } => {
+                self.add_node(zid, *whatami, when);
+                let node = self.nodes.get_mut(zid).unwrap();
+                // The same state is relayed by every router, and older ones may come late.
+                if node.sn.is_some_and(|last| last >= *sn) {
+                    return &self.changes[logged..];
+                }
+                node.sn = Some(*sn);
+                let previous = std::mem::replace(&mut node.links, links.clone());
+                for other in previous.union(links) {
+                    self.add_node(other, None, when);
+                    self.refresh_link(zid, other, when);
+                }
+            }
+        }
+        &self.changes[logged..]
+    }
+
+    fn add_node(&mut self, zid: &str, whatami: Option<WhatAmI>, when: When) {
+        let Some(node) = self.nodes.get_mut(zid) else {
+            self.nodes.insert(
+                zid.to_string(),
+                Node {
+                    whatami,
+                    first_seen: when,
+                    sn: None,
+                    links: BTreeSet::new(),
+                },
+            );
+            let zid = zid.to_string();
+            self.changes
+                .push((when, Change::NodeAdded { zid, whatami }));
+            return;
+        };
+        match (node.whatami, whatami) {
+            (None, Some(_)) => node.whatami = whatami,
+            (Some(old), Some(new)) if old != new => {
+                node.whatami = whatami;
+                let zid = zid.to_string();
+                self.changes
+                    .push((when, Change::RoleChanged { zid, whatami: new }));
+            }
+            _ => {}
+        }
+    }
+
+    /// Bring the link between `a` and `b` up or down according to the sessions and link states,
+    /// logging the change if there's one.
+    fn refresh_link(&mut self, a: &str, b: &str, when: When) {
+        let listed =
+            |from: &str, to: &str| self.nodes.get(from).is_some_and(|n| n.links.contains(to));
+        let listed = listed(a, b) || listed(b, a);
+        let key = link_key(a, b);
+        let link = match self.links.get_mut(&key) {
+            Some(link) => link,
+            None if listed => self.links.entry(key.clone()).or_insert(Link {
+                sessions: 0,
+                up: false,
+                since: when,
+            }),
+            None => return,
+        };
+
+        let up = listed || link.sessions > 0;
+        if up == link.up {
+            return;
+        }
+        link.up = up;
+        link.since = when;
+        let (a, b) = key;
+        let change = match up {
+            true => Change::LinkUp { a, b },
+            false => Change::LinkDown { a, b },
+        };
+        self.changes.push((when, change));
+    }
+
+    /// Writes the graph as it was at the end of the capture as a Graphviz DOT graph. Routers are
+    /// boxes, peers ellipses and clients plain text, and links which went down are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph zenoh {\n");
+        for (zid, node) in &self.nodes {
+            let shape = match node.whatami {
+                Some(WhatAmI::Router) => "box",
+                Some(WhatAmI::Client) => "plaintext",
+                _ => "ellipse",
+            };
+            let label = match node.whatami {
+                Some(whatami) => format!("{zid}\\n{whatami}"),
+                None => zid.clone(),
+            };
+            let _ = writeln!(
+                dot,
+                "  {} [label={}, shape={shape}];",
+                dot_id(zid),
+                dot_id(&label)
+            );
+        }
+        for ((a, b), link) in &self.links {
+            let style = match link.up {
+                true => "solid",
+                false => "dashed",
+            };
+            let _ = writeln!(
+                dot,
+                "  {} -- {} [style={style}, tooltip={}];",
+                dot_id(a),
+                dot_id(b),
+                dot_id(&format!(
+                    "{} since frame {}",
+                    link_state(link),
+                    link.since.frame
+                ))
+            );
+        }
+        dot + "}\n"
+    }
+
+    /// Writes the nodes, links and changes as a JSON object.
+    pub fn to_json(&self) -> String {
+        let when = |when: &When| {
+            format!(
+                "\"frame\": {}, \"time\": {:.6}, \"abs_time\": {:.6}",
+                when.frame, when.rel_time, when.abs_time
+            )
+        };
+        let whatami = |whatami: Option<WhatAmI>| {
+            whatami.map_or("null".to_string(), |whatami| json_str(whatami.to_str()))
+        };
+
+        let nodes = self.nodes.iter().map(|(zid, node)| {
+            format!(
+                "{{\"zid\": {}, \"whatami\": {}, \"first_seen\": {{{}}}}}",
+                json_str(zid),
+                whatami(node.whatami),
+                when(&node.first_seen)
+            )
+        });
+        let links = self.links.iter().map(|((a, b), link)| {
+            format!(
+                "{{\"a\": {}, \"b\": {}, \"up\": {}, \"sessions\": {}, \"since\": {{{}}}}}",
+                json_str(a),
+                json_str(b),
+                link.up,
+                link.sessions,
+                when(&link.since)
+            )
+        });
+        let changes = self.changes.iter().map(|(at, change)| {
+            let fields = match change {
+                Change::NodeAdded { zid, whatami: w } => {
+                    format!("\"zid\": {}, \"whatami\": {}", json_str(zid), whatami(*w))
+                }
+                Change::RoleChanged { zid, whatami: w } => {
+                    format!(
+                        "\"zid\": {}, \"whatami\": {}",
+                        json_str(zid),
+                        whatami(Some(*w))
+                    )
+                }
+                Change::LinkUp { a, b } | Change::LinkDown { a, b } => {
+                    format!("\"a\": {}, \"b\": {}", json_str(a), json_str(b))
+                }
+            };
+            format!(
+                "{{{}, \"change\": \"{}\", {fields}}}",
+                when(at),
+                change.kind()
+            )
+        });
+
+        format!(
+            "{{\n  \"nodes\": {},\n  \"links\": {},\n  \"changes\": {}\n}}\n",
+            json_list(nodes),
+            json_list(links),
+            json_list(changes)
+        )
+    }
+
+    /// Writes the nodes, links and changes as plain text, in the layout of the other tshark
+    /// statistics.
+    pub fn to_text(&self) -> String {
+        let rule = "=".repeat(80);
+        let mut text = format!("{rule}\nZenoh Topology\n");
+        let _ = writeln!(text, "\nNodes: {}", self.nodes.len());
+        for (zid, node) in &self.nodes {
+            let _ = writeln!(
+                text,
+                "  {:<48} first seen in {}",
+                node_label(zid, node.whatami),
+                node.first_seen
+            );
+        }
+        let _ = writeln!(text, "\nLinks: {}", self.links.len());
+        for ((a, b), link) in &self.links {
+            let _ = writeln!(
+                text,
+                "  {a} -- {b}: {} since {}",
+                link_state(link),
+                link.since
+            );
+        }
+        let _ = writeln!(text, "\nChanges: {}", self.changes.len());
+        for (when, change) in &self.changes {
+            let _ = writeln!(text, "  {when}: {change}");
+        }
+        text + &rule + "\n"
+    }
+}
+
+fn link_key(a: &str, b: &str) -> (String, String) {
+    match a <= b {
+        true => (a.to_string(), b.to_string()),
+        false => (b.to_string(), a.to_string()),
+    }
+}
+
+fn link_state(link: &Link) -> &'static str {
+    match link.up {
+        true => "up",
+        false => "down",
+    }
+}
+
+/// Quotes a DOT identifier.
+fn dot_id(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+/// Writes JSON values as an array, one per line.
+fn json_list(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<_> = items.map(|item| format!("    {item}")).collect();
+    match items.is_empty() {
+        true => "[]".to_string(),
+        false => format!("[\n{}\n  ]", items.join(",\n")),
+    }
+}
+
+/// Quotes a JSON string.
+fn json_str(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted + "\""
+}
+
+/// Output format of `-z zenoh,topology`.
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Text,
+    Dot,
+    Json,
+}
+
+/// State of a `-z zenoh,topology` listener.
+#[derive(Debug)]
+struct Listener {
+    format: Format,
+    topology: Topology,
+}
+
+/// Starts a `-z zenoh,topology[,text|dot|json][,filter]` statistics, printing the topology at
+/// the end of the capture. A display filter such as `frame.number <= 1234` gives the topology as
+/// it was at that frame.
+unsafe extern "C" fn cli_init(opt_arg: *const c_char, _userdata: *mut c_void) -> bool {
+    let opt_arg = CStr::from_ptr(opt_arg).to_string_lossy();
+    let args = opt_arg.strip_prefix("zenoh,topology").unwrap_or_default();
+    let args = args.strip_prefix(',').unwrap_or(args);
+    let (format, rest) = args.split_once(',').unwrap_or((args, ""));
+    let (format, filter) = match format {
+        "" | "text" => (Format::Text, rest),
+        "dot" => (Format::Dot, rest),
+        "json" => (Format::Json, rest),
+        _ => (Format::Text, args),
+    };
+    let filter = std::ffi::CString::new(filter).unwrap_or_default();
+
+    let listener = Box::into_raw(Box::new(Listener {
+        format,
+        topology: Topology::new(),
+    }));
+    let error = epan_sys::register_tap_listener(
+        TOPOLOGY_TAP_NAME.as_ptr(),
+        listener as _,
+        if filter.is_empty() {
+            std::ptr::null()
+        } else {
+            filter.as_ptr()
+        },
+        epan_sys::TL_REQUIRES_NOTHING,
+        Some(cli_reset),
+        Some(cli_packet),
+        Some(cli_draw),
+        Some(cli_finish),
+    );
+    if !error.is_null() {
+        let message = CStr::from_ptr((*error).str_);
+        eprintln!(
+            "tshark: Couldn't register zenoh,topology tap: {}",
+            message.to_string_lossy()
+        );
+        epan_sys::g_string_free(error, 1);
+        drop(Box::from_raw(listener));
+        return false;
+    }
+    true
+}
+
+unsafe extern "C" fn cli_reset(tapdata: *mut c_void) {
+    (*(tapdata as *mut Listener)).topology = Topology::new();
+}
+
+unsafe extern "C" fn cli_packet(
+    tapdata: *mut c_void,
+    pinfo: *mut epan_sys::packet_info,
+    _edt: *mut epan_sys::epan_dissect_t,
+    data: *const c_void,
+    _flags: epan_sys::tap_flags_t,
+) -> epan_sys::tap_packet_status {
+    let listener = &mut *(tapdata as *mut Listener);
+    let when = When::of(pinfo);
+    for event in &*(data as *const Vec<Event>) {
+        listener.topology.apply(event, when);
+    }
+    epan_sys::tap_packet_status_TAP_PACKET_REDRAW
+}
+
+unsafe extern "C" fn cli_draw(tapdata: *mut c_void) {
+    let listener = &*(tapdata as *const Listener);
+    let output = match listener.format {
+        Format::Text => listener.topology.to_text(),
+        Format::Dot => listener.topology.to_dot(),
+        Format::Json => listener.topology.to_json(),
+    };
+    print!("{output}");
+}
+
+unsafe extern "C" fn cli_finish(tapdata: *mut c_void) {
+    drop(Box::from_raw(tapdata as *mut Listener));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(frame: u32) -> When {
+        When {
+            frame,
+            rel_time: frame as f64 / 10.0,
+            abs_time: 1000.0 + frame as f64 / 10.0,
+        }
+    }
+
+    fn session(a: &str, b: &str, up: bool) -> Event {
+        Event::Session {
+            a: a.to_string(),
+            b: b.to_string(),
+            up,
+        }
+    }
+
+    fn link_state(zid: &str, sn: u64, links: &[&str]) -> Event {
+        Event::LinkState {
+            zid: zid.to_string(),
+            whatami: Some(WhatAmI::Router),
+            sn,
+            links: links.iter().map(|zid| zid.to_string()).collect(),
+        }
+    }
+
+    fn changes(topology: &mut Topology, event: &Event, frame: u32) -> Vec<String> {
+        topology
+            .apply(event, at(frame))
+            .iter()
+            .map(|(_, change)| change.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn sessions_bring_links_up_and_down() {
+        let mut topology = Topology::new();
+        assert_eq!(
+            changes(&mut topology, &session("b2", "a1", true), 1),
+            ["Node b2 added", "Node a1 added", "Link a1 -- b2 up"]
+        );
+        // A second session on the same link keeps it up until both are closed.
+        assert!(changes(&mut topology, &session("a1", "b2", true), 2).is_empty());
+        assert!(changes(&mut topology, &session("a1", "b2", false), 3).is_empty());
+        assert_eq!(
+            changes(&mut topology, &session("b2", "a1", false), 4),
+            ["Link a1 -- b2 down"]
+        );
+        assert!(changes(&mut topology, &session("b2", "a1", false), 5).is_empty());
+    }
+
+    #[test]
+    fn nodes_change_roles() {
+        let mut topology = Topology::new();
+        let node = |whatami| Event::Node {
+            zid: "a1".to_string(),
+            whatami,
+        };
+        assert_eq!(
+            changes(&mut topology, &node(WhatAmI::Peer), 1),
+            ["Node a1 (peer) added"]
+        );
+        assert!(changes(&mut topology, &node(WhatAmI::Peer), 2).is_empty());
+        assert_eq!(
+            changes(&mut topology, &node(WhatAmI::Router), 3),
+            ["Node a1 is now a router"]
+        );
+    }
+
+    #[test]
+    fn link_states_replace_older_ones() {
+        let mut topology = Topology::new();
+        assert_eq!(
+            changes(&mut topology, &link_state("a1", 2, &["b2"]), 1),
+            [
+                "Node a1 (router) added",
+                "Node b2 added",
+                "Link a1 -- b2 up"
+            ]
+        );
+        // Relayed or late states are ignored.
+        assert!(changes(&mut topology, &link_state("a1", 2, &[]), 2).is_empty());
+        assert!(changes(&mut topology, &link_state("a1", 1, &[]), 3).is_empty());
+        assert_eq!(
+            changes(&mut topology, &link_state("a1", 3, &["c3"]), 4),
+            ["Link a1 -- b2 down", "Node c3 added", "Link a1 -- c3 up"]
+        );
+    }
+
+    #[test]
+    fn link_states_and_sessions_both_hold_links() {
+        let mut topology = Topology::new();
+        topology.apply(&link_state("a1", 1, &["b2"]), at(1));
+        assert!(changes(&mut topology, &session("a1", "b2", true), 2).is_empty());
+        assert!(changes(&mut topology, &link_state("a1", 2, &[]), 3).is_empty());
+        assert_eq!(
+            changes(&mut topology, &session("a1", "b2", false), 4),
+            ["Link a1 -- b2 down"]
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let mut topology = Topology::new();
+        topology.apply(&link_state("a1", 1, &["b\"2"]), at(1));
+        topology.apply(&session("a1", "c3", true), at(2));
+        topology.apply(&session("a1", "c3", false), at(3));
+        assert_eq!(
+            topology.to_dot(),
+            concat!(
+                "graph zenoh {\n",
+                "  \"a1\" [label=\"a1\\nrouter\", shape=box];\n",
+                "  \"b\\\"2\" [label=\"b\\\"2\", shape=ellipse];\n",
+                "  \"c3\" [label=\"c3\", shape=ellipse];\n",
+                "  \"a1\" -- \"b\\\"2\" [style=solid, tooltip=\"up since frame 1\"];\n",
+                "  \"a1\" -- \"c3\" [style=dashed, tooltip=\"down since frame 3\"];\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn json() {
+        let mut topology = Topology::new();
+        assert_eq!(
+            topology.to_json(),
+            "{\n  \"nodes\": [],\n  \"links\": [],\n  \"changes\": []\n}\n"
+        );
+        topology.apply(&session("a1", "b2", true), at(1));
+        topology.apply(
+            &Event::Node {
+                zid: "b2".to_string(),
+                whatami: WhatAmI::Client,
+            },
+            at(2),
+        );
+        assert_eq!(
+            topology.to_json(),
+            concat!(
+                "{\n",
+                "  \"nodes\": [\n",
+                "    {\"zid\": \"a1\", \"whatami\": null, \"first_seen\": {\"frame\": 1, \"time\": 0.100000, \"abs_time\": 1000.100000}},\n",
+                "    {\"zid\": \"b2\", \"whatami\": \"client\", \"first_seen\": {\"frame\": 1, \"time\": 0.100000, \"abs_time\": 1000.100000}}\n",
+                "  ],\n",
+                "  \"links\": [\n",
+                "    {\"a\": \"a1\", \"b\": \"b2\", \"up\": true, \"sessions\": 1, \"since\": {\"frame\": 1, \"time\": 0.100000, \"abs_time\": 1000.100000}}\n",
+                "  ],\n",
+                "  \"changes\": [\n",
+                "    {\"frame\": 1, \"time\": 0.100000, \"abs_time\": 1000.100000, \"change\": \"node_added\", \"zid\": \"a1\", \"whatami\": null},\n",
+                "    {\"frame\": 1, \"time\": 0.100000, \"abs_time\": 1000.100000, \"change\": \"node_added\", \"zid\": \"b2\", \"whatami\": null},\n",
+                "    {\"frame\": 1, \"time\": 0.100000, \"abs_time\": 1000.100000, \"change\": \"link_up\", \"a\": \"a1\", \"b\": \"b2\"}\n",
+                "  ]\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn json_strings() {
+        assert_eq!(json_str("a1"), "\"a1\"");
+        assert_eq!(json_str("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(json_str("a\nb\u{7f}"), "\"a\\u000ab\\u007f\"");
+        assert_eq!(json_str("ключ"), "\"ключ\"");
+    }
+}
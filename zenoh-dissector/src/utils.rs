@@ -3,10 +3,20 @@ use std::{
     error::Error,
     ffi::{c_char, CString},
 };
-use zenoh_buffers::ZSlice;
+use zenoh_buffers::buffer::SplitBuffer;
+use zenoh_buffers::{
+    reader::{HasReader, Reader},
+    ZSlice,
+};
+use zenoh_codec::{RCodec, Zenoh080, Zenoh080Header, Zenoh080Reliability};
 use zenoh_protocol::{
+    common::imsg,
+    core::Encoding,
     network::{NetworkBody, NetworkMessage},
-    transport::{BatchSize, TransportMessage},
+    transport::{
+        frame::FrameHeader, id, BatchSize, Frame, TransportBodyLowLatency, TransportMessage,
+        TransportMessageLowLatency,
+    },
 };
 use zenoh_transport::common::batch::{BatchConfig, RBatch};
 
@@ -14,16 +24,22 @@ pub fn leak_nul_terminated_str(s: &str) -> Result<*const c_char> {
     Ok(Box::leak(CString::new(s)?.into_boxed_c_str()).as_ptr())
 }
 
+/// Summary of the messages of a packet for the Info column, holding at most `limit` entries of
+/// at most `length_limit` characters each (0 for no limit).
 pub struct SizedSummary {
+    limit: usize,
+    length_limit: usize,
     is_full: bool,
     data: Vec<String>,
 }
 
 impl SizedSummary {
-    pub fn new(limit: usize) -> Self {
+    pub fn new(limit: usize, length_limit: usize) -> Self {
         Self {
+            limit,
+            length_limit,
             is_full: false,
-            data: Vec::with_capacity(limit),
+            data: Vec::new(),
         }
     }
 
@@ -31,26 +47,50 @@ impl SizedSummary {
         self.data.len()
     }
 
-    pub fn append<F>(&mut self, generate_msg: F)
+    /// Append the summaries of a message, only generated if there's still room for them.
+    pub fn append<F>(&mut self, generate_msgs: F)
     where
-        F: FnOnce() -> String,
+        F: FnOnce() -> Vec<String>,
     {
-        if !self.is_full {
-            if self.data.len() >= self.data.capacity() {
+        if self.is_full {
+            return;
+        }
+        for msg in generate_msgs() {
+            if self.limit != 0 && self.data.len() >= self.limit {
                 self.is_full = true;
-            } else {
-                self.data.push(generate_msg());
+                return;
             }
+            self.data.push(truncate_summary(msg, self.length_limit));
         }
     }
 }
 
+/// Truncate a summary to `limit` characters and mark it with an ellipsis. The parenthesised
+/// details of a summary are cut at a word boundary, dropping them entirely if need be, while its
+/// message type, id and key expression keep their leading characters, e.g. `Push robot/1/ca…`.
+fn truncate_summary(msg: String, limit: usize) -> String {
+    if limit == 0 || msg.chars().count() <= limit {
+        return msg;
+    }
+    // Keep room for the ellipsis.
+    let end = msg
+        .char_indices()
+        .nth(limit.saturating_sub(1))
+        .map_or(msg.len(), |(i, _)| i);
+    let kept = &msg[..end];
+    let kept = match (kept.find(" ("), kept.rfind(' ')) {
+        (Some(details), Some(space)) if space >= details => &kept[..space],
+        _ => kept,
+    };
+    format!("{}\u{2026}", kept.trim_end_matches([' ', ',', '(']))
+}
+
 impl std::fmt::Display for SizedSummary {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let content = if self.len() > 1 {
+        let content = if self.len() > 1 || self.is_full {
             format!(
                 "[{}]",
-                self.data.join(", ") + if self.is_full { ", ..." } else { "" }
+                self.data.join(", ") + if self.is_full { ", \u{2026}" } else { "" }
             )
         } else if self.data.is_empty() {
             "Empty".to_string()
@@ -71,21 +111,76 @@ pub(crate) fn new_rbatch(
         is_streamed: false,
         is_compression: compression,
     };
-    let mut rbatch = RBatch::new(config, zslice.clone());
-    if rbatch.initialize(|| vec![0; config.mtu as usize]).is_err() {
-        // In case TransportMessage like InitAck are not compressed, try to read without assuming
-        // compression
-        let config = BatchConfig {
-            mtu: BatchSize::MAX,
-            is_streamed: false,
-            is_compression: false,
+    let mut rbatch = RBatch::new(config, zslice);
+    rbatch
+        .initialize(|| vec![0; config.mtu as usize])
+        .map_err(|err| err.to_string())?;
+    Ok(rbatch)
+}
+
+/// Same as [`new_rbatch`], but retries without compression if the batch can't be read as
+/// compressed. Only used when the session's negotiation is unknown. Also returns whether the
+/// batch was read with compression enabled, i.e. with a batch header.
+pub(crate) fn new_rbatch_with_fallback(
+    batch: &[u8],
+    compression: bool,
+) -> Result<(RBatch<ZSlice>, bool), Box<dyn Error>> {
+    new_rbatch(batch, compression)
+        .map(|rbatch| (rbatch, compression))
+        .or_else(|err| {
+            if compression {
+                // In case TransportMessage like InitAck are not compressed, try to read without
+                // assuming compression
+                new_rbatch(batch, false).map(|rbatch| (rbatch, false))
+            } else {
+                Err(err)
+            }
+        })
+}
+
+/// Decodes a transport message at the start of an uncompressed batch payload, along with the
+/// length of each network message of a Frame, which the decoder of `zenoh_codec` doesn't tell.
+/// Frames are read the same way, i.e. up to the first bytes which aren't a network message.
+pub(crate) fn decode_transport(reader: &mut &[u8]) -> Option<(TransportMessage, Vec<usize>)> {
+    let start = *reader;
+    let header: u8 = Zenoh080::new().read(&mut *reader).ok()?;
+    if imsg::mid(header) != id::FRAME {
+        *reader = start;
+        let msg: TransportMessage = Zenoh080::new().read(&mut *reader).ok()?;
+        return Some((msg, Vec::new()));
+    }
+
+    let header: FrameHeader = Zenoh080Header::new(header).read(&mut *reader).ok()?;
+    let codec = Zenoh080Reliability::new(header.reliability);
+    let mut payload = Vec::new();
+    let mut lens = Vec::new();
+    while reader.can_read() {
+        let mark = *reader;
+        let Ok(msg): Result<NetworkMessage, _> = codec.read(&mut *reader) else {
+            *reader = mark;
+            break;
         };
-        rbatch = RBatch::new(config, zslice);
-        rbatch
-            .initialize(|| vec![0; config.mtu as usize])
-            .map_err(|err| err.to_string())?;
+        payload.push(msg);
+        lens.push(mark.len() - reader.len());
     }
-    Ok(rbatch)
+    let frame = Frame {
+        reliability: header.reliability,
+        sn: header.sn,
+        ext_qos: header.ext_qos,
+        payload,
+    };
+    Some((frame.into(), lens))
+}
+
+/// Decodes a single message of the low-latency transport, which is not batched.
+pub(crate) fn decode_lowlatency(
+    message: &[u8],
+) -> Result<TransportMessageLowLatency, Box<dyn Error>> {
+    let mut reader = message.reader();
+    let msg: TransportMessageLowLatency = Zenoh080::new()
+        .read(&mut reader)
+        .map_err(|_| "failed to decode low-latency message")?;
+    Ok(msg)
 }
 
 pub(crate) fn network_message_summary(msg: &NetworkMessage) -> String {
@@ -101,6 +196,32 @@ pub(crate) fn network_message_summary(msg: &NetworkMessage) -> String {
     }
 }
 
+/// Names of the `Close` reasons, see `zenoh_protocol::transport::close::reason`.
+pub(crate) const CLOSE_REASONS: &[(u32, &str)] = {
+    use zenoh_protocol::transport::close::reason::*;
+    &[
+        (GENERIC as u32, "Generic"),
+        (UNSUPPORTED as u32, "Unsupported"),
+        (INVALID as u32, "Invalid"),
+        (MAX_SESSIONS as u32, "Max sessions"),
+        (MAX_LINKS as u32, "Max links"),
+        (EXPIRED as u32, "Expired"),
+        (UNRESPONSIVE as u32, "Unresponsive"),
+        (CONNECTION_TO_SELF as u32, "Connection to self"),
+    ]
+};
+
+/// Returns the name of a `Close` reason.
+pub(crate) fn close_reason_name(reason: u8) -> String {
+    CLOSE_REASONS
+        .iter()
+        .find(|(value, _)| *value == reason as u32)
+        .map_or_else(
+            || format!("Unknown ({reason})"),
+            |(_, name)| name.to_string(),
+        )
+}
+
 pub(crate) fn transport_message_summary(msg: &TransportMessage) -> String {
     use zenoh_protocol::transport::TransportBody::*;
     match &msg.body {
@@ -109,7 +230,7 @@ pub(crate) fn transport_message_summary(msg: &TransportMessage) -> String {
         InitAck(_) => "InitAck".to_string(),
         OpenSyn(_) => "OpenSyn".to_string(),
         OpenAck(_) => "OpenAck".to_string(),
-        Close(_) => "Close".to_string(),
+        Close(close) => format!("Close({})", close_reason_name(close.reason)),
         KeepAlive(_) => "KeepAlive".to_string(),
         Frame(frame) => {
             "Frame[".to_string()
@@ -125,3 +246,259 @@ pub(crate) fn transport_message_summary(msg: &TransportMessage) -> String {
         Join(_) => "Join".to_string(),
     }
 }
+
+pub(crate) fn lowlatency_message_summary(msg: &TransportMessageLowLatency) -> String {
+    match &msg.body {
+        TransportBodyLowLatency::Close(close) => {
+            format!("Close({})", close_reason_name(close.reason))
+        }
+        TransportBodyLowLatency::KeepAlive(_) => "KeepAlive".to_string(),
+        TransportBodyLowLatency::Network(msg) => network_message_summary(msg),
+    }
+}
+
+/// Names and file extensions of the predefined encodings, indexed by encoding id. See
+/// `zenoh::bytes::Encoding`.
+const ENCODINGS: &[(&str, &str)] = &[
+    ("zenoh/bytes", "bin"),
+    ("zenoh/string", "txt"),
+    ("zenoh/serialized", "bin"),
+    ("application/octet-stream", "bin"),
+    ("text/plain", "txt"),
+    ("application/json", "json"),
+    ("text/json", "json"),
+    ("application/cdr", "cdr"),
+    ("application/cbor", "cbor"),
+    ("application/yaml", "yaml"),
+    ("text/yaml", "yaml"),
+    ("text/json5", "json5"),
+    ("application/python-serialized-object", "pickle"),
+    ("application/protobuf", "pb"),
+    ("application/java-serialized-object", "ser"),
+    ("application/openmetrics-text", "txt"),
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/bmp", "bmp"),
+    ("image/webp", "webp"),
+    ("application/xml", "xml"),
+    ("application/x-www-form-urlencoded", "txt"),
+    ("text/html", "html"),
+    ("text/xml", "xml"),
+    ("text/css", "css"),
+    ("text/javascript", "js"),
+    ("text/markdown", "md"),
+    ("text/csv", "csv"),
+    ("application/sql", "sql"),
+    ("application/coap-payload", "bin"),
+    ("application/json-patch+json", "json"),
+    ("application/json-seq", "json"),
+    ("application/jsonpath", "txt"),
+    ("application/jwt", "jwt"),
+    ("application/mp4", "mp4"),
+    ("application/soap+xml", "xml"),
+    ("application/yang", "yang"),
+    ("audio/aac", "aac"),
+    ("audio/flac", "flac"),
+    ("audio/mp4", "m4a"),
+    ("audio/ogg", "ogg"),
+    ("audio/vorbis", "ogg"),
+    ("video/h261", "h261"),
+    ("video/h263", "h263"),
+    ("video/h264", "h264"),
+    ("video/h265", "h265"),
+    ("video/h266", "h266"),
+    ("video/mp4", "mp4"),
+    ("video/ogg", "ogv"),
+    ("video/raw", "raw"),
+    ("video/vp8", "vp8"),
+    ("video/vp9", "vp9"),
+];
+
+/// Returns the name of an encoding followed by its schema, if any, e.g. `text/plain;utf-8`.
+/// Encodings which aren't predefined are named by their id.
+pub(crate) fn encoding_name(encoding: &Encoding) -> String {
+    let mut name = ENCODINGS.get(encoding.id as usize).map_or_else(
+        || format!("unknown({})", encoding.id),
+        |(name, _)| name.to_string(),
+    );
+    if let Some(schema) = &encoding.schema {
+        name += ";";
+        name += &String::from_utf8_lossy(&schema.contiguous());
+    }
+    name
+}
+
+/// Returns the file extension of an encoding named by [`encoding_name`], `bin` if unknown.
+pub(crate) fn encoding_extension(name: &str) -> &'static str {
+    let mime = name.split(';').next().unwrap_or_default();
+    ENCODINGS
+        .iter()
+        .find(|(name, _)| *name == mime)
+        .map_or("bin", |(_, extension)| extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenoh_buffers::writer::HasWriter;
+    use zenoh_codec::WCodec;
+    use zenoh_protocol::{
+        core::Reliability,
+        network::{response, ResponseFinal},
+        transport::{frame, KeepAlive, TransportBody},
+    };
+
+    fn encode(msg: &TransportMessage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        Zenoh080::new().write(&mut bytes.writer(), msg).unwrap();
+        bytes
+    }
+
+    fn response_final(rid: u32) -> NetworkMessage {
+        NetworkMessage {
+            body: NetworkBody::ResponseFinal(ResponseFinal {
+                rid,
+                ext_qos: response::ext::QoSType::DEFAULT,
+                ext_tstamp: None,
+            }),
+            reliability: Reliability::Reliable,
+        }
+    }
+
+    fn frame(payload: Vec<NetworkMessage>) -> TransportMessage {
+        Frame {
+            reliability: Reliability::Reliable,
+            sn: 42,
+            ext_qos: frame::ext::QoSType::DEFAULT,
+            payload,
+        }
+        .into()
+    }
+
+    #[test]
+    fn truncate_summary_keeps_short_summaries() {
+        assert_eq!(truncate_summary("Push a/b".to_string(), 0), "Push a/b");
+        assert_eq!(truncate_summary("Push a/b".to_string(), 8), "Push a/b");
+    }
+
+    #[test]
+    fn truncate_summary_cuts_details_at_a_word() {
+        let msg = "Push robot/1 (Put, 12 bytes)".to_string();
+        assert_eq!(
+            truncate_summary(msg.clone(), 20),
+            "Push robot/1 (Put\u{2026}"
+        );
+        assert_eq!(truncate_summary(msg, 15), "Push robot/1\u{2026}");
+    }
+
+    #[test]
+    fn truncate_summary_cuts_key_expressions_anywhere() {
+        let msg = "Push robot/1/camera".to_string();
+        assert_eq!(truncate_summary(msg, 12), "Push robot/\u{2026}");
+    }
+
+    #[test]
+    fn truncate_summary_counts_characters() {
+        // Cutting within a multi-byte character would panic.
+        let msg = "Push ключ/значение".to_string();
+        let truncated = truncate_summary(msg, 10);
+        assert_eq!(truncated, "Push ключ\u{2026}");
+        assert_eq!(truncated.chars().count(), 10);
+    }
+
+    #[test]
+    fn sized_summary_display() {
+        let mut summary = SizedSummary::new(2, 0);
+        assert_eq!(summary.to_string(), "Empty");
+        summary.append(|| vec!["KeepAlive".to_string()]);
+        assert_eq!(summary.to_string(), "KeepAlive");
+        summary.append(|| vec!["Push a".to_string()]);
+        assert_eq!(summary.to_string(), "[KeepAlive, Push a]");
+    }
+
+    #[test]
+    fn sized_summary_stops_when_full() {
+        let mut summary = SizedSummary::new(2, 6);
+        summary.append(|| vec!["Push a".to_string(), "Push robot/1".to_string()]);
+        summary.append(|| vec!["Push b".to_string()]);
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary.to_string(), "[Push a, Push\u{2026}, \u{2026}]");
+        summary.append(|| panic!("summaries generated for a full summary"));
+    }
+
+    #[test]
+    fn decode_transport_tells_network_message_lengths() {
+        let msgs = vec![response_final(1), response_final(1 << 20)];
+        let bytes = encode(&frame(msgs.clone()));
+        let lens: Vec<usize> = msgs
+            .iter()
+            .map(|msg| encode(&frame(vec![msg.clone()])).len() - encode(&frame(vec![])).len())
+            .collect();
+
+        let mut reader = bytes.as_slice();
+        let (msg, network_lens) = decode_transport(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(network_lens, lens);
+        assert_ne!(lens[0], lens[1]);
+        let TransportBody::Frame(decoded) = msg.body else {
+            panic!("not a Frame: {msg:?}");
+        };
+        assert_eq!(decoded.payload, msgs);
+    }
+
+    #[test]
+    fn decode_transport_reads_one_message() {
+        let keep_alive = encode(&KeepAlive.into());
+        let frame = encode(&frame(vec![response_final(1)]));
+        let bytes = [keep_alive.as_slice(), frame.as_slice()].concat();
+
+        let mut reader = bytes.as_slice();
+        let (msg, network_lens) = decode_transport(&mut reader).unwrap();
+        assert!(matches!(msg.body, TransportBody::KeepAlive(_)));
+        assert!(network_lens.is_empty());
+        assert_eq!(reader, frame.as_slice());
+    }
+
+    #[test]
+    fn decode_transport_fails_on_garbage() {
+        let mut reader: &[u8] = &[];
+        assert!(decode_transport(&mut reader).is_none());
+        let mut reader: &[u8] = &[0x1f];
+        assert!(decode_transport(&mut reader).is_none());
+    }
+
+    #[test]
+    fn decode_lowlatency_messages() {
+        let msg = decode_lowlatency(&[id::CLOSE, 0x02]).unwrap();
+        let TransportBodyLowLatency::Close(close) = msg.body else {
+            panic!("not a Close: {msg:?}");
+        };
+        assert_eq!(close.reason, 0x02);
+        let msg = decode_lowlatency(&[id::KEEP_ALIVE]).unwrap();
+        assert!(matches!(msg.body, TransportBodyLowLatency::KeepAlive(_)));
+        assert!(decode_lowlatency(&[]).is_err());
+    }
+
+    #[test]
+    fn encoding_names() {
+        let encoding = |id, schema: Option<&str>| Encoding {
+            id,
+            schema: schema.map(|schema| ZSlice::from(schema.as_bytes().to_vec())),
+        };
+        assert_eq!(encoding_name(&encoding(0, None)), "zenoh/bytes");
+        assert_eq!(
+            encoding_name(&encoding(4, Some("utf-8"))),
+            "text/plain;utf-8"
+        );
+        assert_eq!(encoding_name(&encoding(9999, None)), "unknown(9999)");
+    }
+
+    #[test]
+    fn encoding_extensions() {
+        assert_eq!(encoding_extension("image/png"), "png");
+        assert_eq!(encoding_extension("text/plain;utf-8"), "txt");
+        assert_eq!(encoding_extension("unknown(9999)"), "bin");
+        assert_eq!(encoding_extension(""), "bin");
+    }
+}
@@ -13,14 +13,30 @@ impl FieldKind {
                 epan_sys::field_display_e_BASE_NONE,
                 epan_sys::ftenum_FT_NONE,
             ),
-            // Self::Number => (
-            //     epan_sys::field_display_e_BASE_DEC,
-            //     epan_sys::ftenum_FT_UINT8,
-            // ),
-            // Self::Bytes => (
-            //     epan_sys::field_display_e_SEP_SPACE,
-            //     epan_sys::ftenum_FT_BYTES,
-            // ),
+            Self::Number => (
+                epan_sys::field_display_e_BASE_DEC,
+                epan_sys::ftenum_FT_UINT64,
+            ),
+            Self::Boolean => (
+                epan_sys::field_display_e_BASE_NONE,
+                epan_sys::ftenum_FT_BOOLEAN,
+            ),
+            Self::FrameNum => (
+                epan_sys::field_display_e_BASE_NONE,
+                epan_sys::ftenum_FT_FRAMENUM,
+            ),
+            Self::Duration => (
+                epan_sys::field_display_e_BASE_NONE,
+                epan_sys::ftenum_FT_RELATIVE_TIME,
+            ),
+            Self::Enum(_) => (
+                epan_sys::field_display_e_BASE_DEC,
+                epan_sys::ftenum_FT_UINT32,
+            ),
+            Self::Bytes => (
+                epan_sys::field_display_e_SEP_SPACE,
+                epan_sys::ftenum_FT_BYTES,
+            ),
         }
     }
 }
@@ -34,6 +50,10 @@ pub fn register_header_field(
     let hf_index_ptr = Box::leak(Box::new(-1)) as *mut _;
 
     let (field_display, field_type) = field_kind.convert();
+    let strings = match field_kind {
+        FieldKind::Enum(values) => leak_value_string(values)?,
+        _ => std::ptr::null(),
+    };
     let hf_register_info = epan_sys::hf_register_info {
         p_id: hf_index_ptr,
         // We have no choice but to leak the strings here,
@@ -44,7 +64,7 @@ pub fn register_header_field(
             abbrev: leak_nul_terminated_str(filter_name)?,
             type_: field_type,
             display: field_display as _,
-            strings: std::ptr::null(),
+            strings: strings as _,
             bitmask: 0,
             blurb: std::ptr::null(),
             id: -1,
@@ -62,3 +82,35 @@ pub fn register_header_field(
     debug_assert_ne!(unsafe { *hf_index_ptr }, -1);
     Ok(unsafe { *hf_index_ptr })
 }
+
+/// Build a `value_string` array, terminated by a null entry as Wireshark expects.
+fn leak_value_string(values: &[(u32, &str)]) -> Result<*const epan_sys::value_string> {
+    let mut vs = Vec::with_capacity(values.len() + 1);
+    for &(value, name) in values {
+        vs.push(epan_sys::value_string {
+            value,
+            strptr: leak_nul_terminated_str(name)?,
+        });
+    }
+    vs.push(epan_sys::value_string {
+        value: 0,
+        strptr: std::ptr::null(),
+    });
+    Ok(vs.leak().as_ptr())
+}
+
+/// Mark a tree item as generated, i.e. not present in the captured bytes but derived from
+/// other frames. Rust counterpart of the inline `proto_item_set_generated` of `proto.h`.
+pub unsafe fn set_generated(item: *mut epan_sys::proto_item) {
+    if !item.is_null() && !(*item).finfo.is_null() {
+        (*(*item).finfo).flags |= epan_sys::FI_GENERATED;
+    }
+}
+
+/// Hide a tree item from the tree while keeping it filterable. Rust counterpart of the inline
+/// `proto_item_set_hidden` of `proto.h`.
+pub unsafe fn set_hidden(item: *mut epan_sys::proto_item) {
+    if !item.is_null() && !(*item).finfo.is_null() {
+        (*(*item).finfo).flags |= epan_sys::FI_HIDDEN;
+    }
+}
@@ -6,23 +6,95 @@ use convert_case::{Case, Casing};
 
 pub struct ZenohProtocol;
 
+/// Add a field showing the `Debug` representation of a value, like the fields of
+/// `impl_for_struct!`.
+unsafe fn add_debug_text(args: &TreeArgs, key: &str, value: &impl std::fmt::Debug) -> Result<()> {
+    let text = std::ffi::CString::new(format!("{value:?}"))?;
+    epan_sys::proto_tree_add_string(
+        args.tree,
+        args.get_hf(key)?,
+        args.tvb,
+        args.start as _,
+        0,
+        text.as_ptr(),
+    );
+    Ok(())
+}
+
 mod impl_for_zenoh_protocol {
     use super::ZenohProtocol;
     use crate::header_field::{FieldKind, HeaderFieldMap, Registration};
-    use zenoh_protocol::transport::TransportMessage;
+    use zenoh_protocol::{
+        network::NetworkMessage,
+        scouting::ScoutingMessage,
+        transport::{TransportMessage, TransportMessageLowLatency},
+    };
 
     impl Registration for ZenohProtocol {
         fn generate_hf_map(prefix: &str) -> HeaderFieldMap {
             let mut hf_map = HeaderFieldMap::new()
                 .add(prefix.to_string(), "Zenoh Protocol", FieldKind::Branch)
-                .add(format!("{prefix}.batch"), "Batch", FieldKind::Branch);
+                .add(format!("{prefix}.batch"), "Batch", FieldKind::Branch)
+                .add(
+                    format!("{prefix}.batch.length"),
+                    "Length",
+                    FieldKind::Number,
+                )
+                .add(
+                    format!("{prefix}.batch.header"),
+                    "Header",
+                    FieldKind::Number,
+                )
+                .add(
+                    format!("{prefix}.batch.compressed"),
+                    "Compressed",
+                    FieldKind::Boolean,
+                )
+                .add(
+                    format!("{prefix}.skipped"),
+                    "Skipped Bytes",
+                    FieldKind::Number,
+                )
+                .add(
+                    format!("{prefix}.reassembled"),
+                    "Reassembled Network Message",
+                    FieldKind::Branch,
+                )
+                .add(
+                    format!("{prefix}.reassembled.first_frame"),
+                    "First Fragment Frame",
+                    FieldKind::FrameNum,
+                )
+                .add(
+                    format!("{prefix}.reassembled.fragment_count"),
+                    "Fragment Count",
+                    FieldKind::Number,
+                );
             hf_map.extend(TransportMessage::generate_hf_map(prefix));
+            hf_map.extend(TransportMessageLowLatency::generate_hf_map(prefix));
+            hf_map.extend(ScoutingMessage::generate_hf_map(prefix));
+            // Network messages reassembled from the Fragments of a session.
+            hf_map.extend(NetworkMessage::generate_hf_map(&format!(
+                "{prefix}.reassembled"
+            )));
             hf_map
         }
 
         fn generate_subtree_names(prefix: &str) -> Vec<String> {
-            let mut names = vec![prefix.to_string(), format!("{prefix}.batch")];
+            let mut names = vec![
+                prefix.to_string(),
+                format!("{prefix}.batch"),
+                format!("{prefix}.reassembled"),
+            ];
             names.extend(TransportMessage::generate_subtree_names(prefix));
+            names.extend(NetworkMessage::generate_subtree_names(&format!(
+                "{prefix}.reassembled"
+            )));
+            // Close and KeepAlive are shared with the low-latency transport.
+            names.extend(TransportMessageLowLatency::generate_subtree_names(prefix));
+            names.extend(ScoutingMessage::generate_subtree_names(prefix));
+            names.sort();
+            names.dedup();
             names
         }
     }
@@ -33,11 +105,16 @@ mod impl_for_transport {
         network::NetworkMessage,
         transport::{
             Close, Fragment, Frame, InitAck, InitSyn, Join, KeepAlive, Oam, OpenAck, OpenSyn,
-            TransportBody, TransportMessage,
+            TransportBody, TransportBodyLowLatency as LowLatencyBody, TransportMessage,
+            TransportMessageLowLatency,
         },
     };
 
-    use crate::zenoh_impl::*;
+    use crate::{
+        oam,
+        utils::{close_reason_name, CLOSE_REASONS},
+        zenoh_impl::*,
+    };
 
     // InitAck
     impl_for_struct! {
@@ -112,10 +189,60 @@ mod impl_for_transport {
     }
 
     // Close
-    impl_for_struct! {
-        struct Close {
-            reason: u8,
-            session: bool,
+    //
+    // Implemented by hand so that the reason is shown by name and the subtree label tells
+    // whether the whole session or only the link was closed.
+    impl Registration for Close {
+        fn generate_hf_map(prefix: &str) -> HeaderFieldMap {
+            HeaderFieldMap::new()
+                .add(
+                    format!("{prefix}.reason"),
+                    "Reason",
+                    FieldKind::Enum(CLOSE_REASONS),
+                )
+                .add(format!("{prefix}.session"), "Session", FieldKind::Boolean)
+        }
+
+        fn generate_subtree_names(_prefix: &str) -> Vec<String> {
+            vec![]
+        }
+    }
+
+    impl AddToTree for Close {
+        fn add_to_tree(&self, prefix: &str, args: &TreeArgs) -> Result<()> {
+            unsafe {
+                epan_sys::proto_tree_add_uint(
+                    args.tree,
+                    args.get_hf(&format!("{prefix}.reason"))?,
+                    args.tvb,
+                    args.start as _,
+                    0,
+                    self.reason as _,
+                );
+                epan_sys::proto_tree_add_boolean(
+                    args.tree,
+                    args.get_hf(&format!("{prefix}.session"))?,
+                    args.tvb,
+                    args.start as _,
+                    0,
+                    self.session as _,
+                );
+
+                let scope = if self.session {
+                    "session-wide"
+                } else {
+                    "link only"
+                };
+                let text = std::ffi::CString::new(format!(
+                    ", Reason: {}, {scope}",
+                    close_reason_name(self.reason)
+                ))?;
+                epan_sys::proto_item_append_text(
+                    epan_sys::proto_tree_get_parent(args.tree),
+                    text.as_ptr(),
+                );
+            }
+            Ok(())
         }
     }
 
@@ -149,11 +276,29 @@ mod impl_for_transport {
     }
 
     // OAM
-    impl_for_struct! {
-        struct Oam {
-            id: OamId,
-            body: ZExtBody,
-            ext_qos: QoSType,
+    //
+    // Implemented by hand so that known ids are named and decoded, and the body shows its
+    // encoding and raw value as typed fields, see `oam`.
+    impl Registration for Oam {
+        fn generate_hf_map(prefix: &str) -> HeaderFieldMap {
+            oam::generate_hf_map(prefix, oam::TRANSPORT_OAMS).add(
+                format!("{prefix}.ext_qos"),
+                "Ext Qos",
+                FieldKind::Text,
+            )
+        }
+
+        fn generate_subtree_names(prefix: &str) -> Vec<String> {
+            oam::generate_subtree_names(prefix, oam::TRANSPORT_OAMS)
+        }
+    }
+
+    impl AddToTree for Oam {
+        fn add_to_tree(&self, prefix: &str, args: &TreeArgs) -> Result<()> {
+            unsafe {
+                oam::add_to_tree(prefix, args, oam::TRANSPORT_OAMS, self.id, &self.body)?;
+                add_debug_text(args, &format!("{prefix}.ext_qos"), &self.ext_qos)
+            }
         }
     }
 
@@ -196,6 +341,64 @@ mod impl_for_transport {
             body: TransportBody,
         }
     }
+
+    // TransportBodyLowLatency
+    impl_for_enum! {
+        enum LowLatencyBody {
+            Close(Close),
+            KeepAlive(KeepAlive),
+            Network(NetworkMessage),
+        }
+    }
+
+    // TransportMessageLowLatency
+    impl_for_struct! {
+        struct TransportMessageLowLatency {
+            #[dissect(expand_as = "transport")]
+            body: LowLatencyBody,
+        }
+    }
+}
+
+mod impl_for_scouting {
+    use zenoh_protocol::scouting::{HelloProto, Scout, ScoutingBody, ScoutingMessage};
+
+    use crate::zenoh_impl::*;
+
+    // Scout
+    impl_for_struct! {
+        struct Scout {
+            version: u8,
+            what: WhatAmIMatcher,
+            zid: Option<ZenohId>,
+        }
+    }
+
+    // Hello
+    impl_for_struct! {
+        struct HelloProto {
+            version: u8,
+            whatami: WhatAmI,
+            zid: ZenohId,
+            locators: Vec<Locator>,
+        }
+    }
+
+    // ScoutingBody
+    impl_for_enum! {
+        enum ScoutingBody {
+            Scout(Scout),
+            Hello(HelloProto),
+        }
+    }
+
+    // ScoutingMessage
+    impl_for_struct! {
+        struct ScoutingMessage {
+            #[dissect(expand_as = "scouting")]
+            body: ScoutingBody,
+        }
+    }
 }
 
 mod impl_for_zenoh {
@@ -296,7 +499,7 @@ mod impl_for_network {
         zenoh::{PushBody, RequestBody, ResponseBody},
     };
 
-    use crate::zenoh_impl::*;
+    use crate::{oam, zenoh_impl::*};
 
     // Push
     impl_for_struct! {
@@ -468,12 +671,31 @@ mod impl_for_network {
     }
 
     // Oam
-    impl_for_struct! {
-        struct Oam {
-            id: OamId,
-            ext_qos: QoSType,
-            ext_tstamp: Option<TimestampType>,
-            body: ZExtBody,
+    //
+    // Implemented by hand like the transport OAM.
+    impl Registration for Oam {
+        fn generate_hf_map(prefix: &str) -> HeaderFieldMap {
+            oam::generate_hf_map(prefix, oam::NETWORK_OAMS)
+                .add(format!("{prefix}.ext_qos"), "Ext Qos", FieldKind::Text)
+                .add(
+                    format!("{prefix}.ext_tstamp"),
+                    "Ext Tstamp",
+                    FieldKind::Text,
+                )
+        }
+
+        fn generate_subtree_names(prefix: &str) -> Vec<String> {
+            oam::generate_subtree_names(prefix, oam::NETWORK_OAMS)
+        }
+    }
+
+    impl AddToTree for Oam {
+        fn add_to_tree(&self, prefix: &str, args: &TreeArgs) -> Result<()> {
+            unsafe {
+                oam::add_to_tree(prefix, args, oam::NETWORK_OAMS, self.id, &self.body)?;
+                add_debug_text(args, &format!("{prefix}.ext_qos"), &self.ext_qos)?;
+                add_debug_text(args, &format!("{prefix}.ext_tstamp"), &self.ext_tstamp)
+            }
         }
     }
 